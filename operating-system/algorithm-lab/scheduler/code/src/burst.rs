@@ -0,0 +1,99 @@
+use std::fmt;
+
+/// Busy ticks of the CPU and the I/O device over a run of the engine.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BurstReport {
+    pub cpu_busy: u32,
+    pub io_busy: u32,
+    /// Ticks from 0 until the last job finished.
+    pub makespan: u32,
+}
+
+impl BurstReport {
+    pub fn cpu_utilization(&self) -> f32 {
+        match self.makespan {
+            0 => 0.0,
+            makespan => self.cpu_busy as f32 / makespan as f32,
+        }
+    }
+
+    pub fn io_utilization(&self) -> f32 {
+        match self.makespan {
+            0 => 0.0,
+            makespan => self.io_busy as f32 / makespan as f32,
+        }
+    }
+}
+
+impl fmt::Display for BurstReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CPU 利用率: {:.1}%, I/O 设备利用率: {:.1}%",
+            self.cpu_utilization() * 100.0,
+            self.io_utilization() * 100.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fcfs::FcfsScheduler, rr::RrScheduler, sjf::SjfScheduler, Burst, Job, Schedule, Scheduler,
+        SchedulerConfig,
+    };
+
+    /// A CPU-bound job and an interactive one that mostly waits for I/O.
+    fn build_burst_jobs() -> Vec<Job> {
+        vec![
+            Job::new("A", 0, 10),
+            Job::with_bursts("B", 0, Burst::alternating(&[1, 3, 1, 3, 1])),
+        ]
+    }
+
+    fn finish_times(schedule: &Schedule) -> Vec<u32> {
+        ["A", "B"]
+            .iter()
+            .map(|name| schedule.finish_time(name).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test() {
+        let config = SchedulerConfig::default().quantum(2);
+
+        let mut scheduler = FcfsScheduler::new(build_burst_jobs());
+        let schedule = scheduler.run(&config);
+        schedule.print();
+
+        // B waits behind the whole of A, then the CPU idles during its I/O
+        let report = scheduler.bursts.unwrap();
+        assert_eq!(finish_times(&schedule), vec![10, 19]);
+        assert_eq!(
+            (report.cpu_busy, report.io_busy, report.makespan),
+            (13, 6, 19)
+        );
+
+        let schedule = SjfScheduler::new(build_burst_jobs()).run(&config);
+        schedule.print();
+        assert_eq!(finish_times(&schedule), vec![11, 16]);
+
+        // B gets the CPU within a slice of every I/O completion, A fills the gaps
+        let mut scheduler = RrScheduler::new(build_burst_jobs());
+        let schedule = scheduler.run(&config);
+        schedule.print();
+
+        let report = scheduler.bursts.unwrap();
+        assert_eq!(finish_times(&schedule), vec![12, 13]);
+        assert_eq!(report.cpu_utilization(), 1.0);
+
+        let transitions = |status: &str| {
+            schedule
+                .events
+                .iter()
+                .filter(|process| process.status == status)
+                .count()
+        };
+        assert_eq!((transitions("BLOCKED"), transitions("READY")), (2, 2));
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+/// Weight of a nice 0 job.
+pub const NICE_0_LOAD: u64 = 1024;
+
+/// `sched_prio_to_weight` of the Linux kernel, nice -20 first. Every nice level is
+/// about 10% of CPU time away from its neighbours.
+const PRIO_TO_WEIGHT: [u64; 40] = [
+    88761, 71755, 56483, 46273, 36291, //
+    29154, 23254, 18705, 14949, 11916, //
+    9548, 7620, 6100, 4904, 3906, //
+    3121, 2501, 1991, 1586, 1277, //
+    1024, 820, 655, 526, 423, //
+    335, 272, 215, 172, 137, //
+    110, 87, 70, 56, 45, //
+    36, 29, 23, 18, 15,
+];
+
+pub fn nice_to_weight(nice: i32) -> u64 {
+    PRIO_TO_WEIGHT[(nice.clamp(-20, 19) + 20) as usize]
+}
+
+/// Virtual runtime of `ticks` real ticks, one tick of a nice 0 job is `NICE_0_LOAD`.
+fn vruntime_of(ticks: u64, weight: u64) -> u64 {
+    ticks * NICE_0_LOAD * NICE_0_LOAD / weight
+}
+
+/// The timeline is ordered by `(vruntime, index)` and the leftmost job runs next.
+/// The kernel keeps it in a red-black tree, `BTreeSet` gives the same ordered
+/// insert / pop-leftmost operations.
+pub struct CfsScheduler {
+    jobs: Vec<Job>,
+    latency: u32,
+    min_granularity: u32,
+    start_debit: bool,
+}
+
+impl CfsScheduler {
+    /// Period in which every runnable job should run once.
+    pub fn latency(mut self, latency: u32) -> CfsScheduler {
+        assert!(latency > 0, "latency must be positive");
+
+        self.latency = latency;
+        self
+    }
+
+    /// Shortest slice a job gets, the period grows when too many jobs are runnable.
+    pub fn min_granularity(mut self, min_granularity: u32) -> CfsScheduler {
+        assert!(min_granularity > 0, "minimum granularity must be positive");
+
+        self.min_granularity = min_granularity;
+        self
+    }
+
+    /// New jobs start one virtual slice after `min_vruntime` instead of at it.
+    pub fn start_debit(mut self, start_debit: bool) -> CfsScheduler {
+        self.start_debit = start_debit;
+        self
+    }
+}
+
+/// The runnable jobs waiting on the timeline, the running job is off it.
+struct CfsTimeline {
+    latency: u32,
+    min_granularity: u32,
+    start_debit: bool,
+    weights: Vec<u64>,
+    vruntimes: Vec<u64>,
+    timeline: BTreeSet<(u64, usize)>,
+    /// Weights of the jobs on the timeline.
+    timeline_weight: u64,
+    // never goes backwards, new and waking jobs are placed relative to it
+    min_vruntime: u64,
+    /// Set when a new job is far behind the running one.
+    wakeup: Option<String>,
+}
+
+impl CfsTimeline {
+    /// Ticks `weight` may run before it is put back on the timeline.
+    fn slice(&self, weight: u64, runnable: u32, total_weight: u64) -> u32 {
+        let period = self.latency.max(runnable * self.min_granularity) as u64;
+
+        ((period * weight / total_weight) as u32).max(self.min_granularity)
+    }
+
+    fn insert(&mut self, job: usize) {
+        self.timeline.insert((self.vruntimes[job], job));
+        self.timeline_weight += self.weights[job];
+    }
+
+    fn vruntime_detail(&self, ctx: &Context, runnable: &[usize]) -> String {
+        let vruntimes = runnable
+            .iter()
+            .map(|&x| {
+                format!(
+                    "{}={:.2}",
+                    ctx.jobs[x].name,
+                    self.vruntimes[x] as f32 / NICE_0_LOAD as f32
+                )
+            })
+            .collect::<Vec<String>>();
+
+        format!("VRUNTIME: {}", vruntimes.join(" "))
+    }
+}
+
+impl Policy for CfsTimeline {
+    // ENQUEUE: new jobs start at min_vruntime, waking jobs no earlier
+    fn enqueue(&mut self, job: usize, ctx: &mut Context) {
+        let weight = self.weights[job];
+        let running = ctx.running(0);
+        let mut start = self.min_vruntime.max(self.vruntimes[job]);
+
+        if self.start_debit && ctx.jobs[job].served_time == 0 {
+            let runnable = self.timeline.len() as u32 + running.iter().count() as u32 + 1;
+            let total_weight =
+                self.timeline_weight + running.map(|x| self.weights[x]).unwrap_or(0) + weight;
+            let ticks = self.slice(weight, runnable, total_weight);
+            start += vruntime_of(ticks as u64, weight);
+        }
+
+        self.vruntimes[job] = start;
+        self.insert(job);
+
+        // WAKEUP PREEMPTION: the new job is far behind the running one
+        if let Some(current) = running {
+            let granularity = vruntime_of(self.min_granularity as u64, NICE_0_LOAD);
+            if self.wakeup.is_none() && self.vruntimes[current] > start + granularity {
+                self.wakeup = Some(format!("by {}", ctx.jobs[job].name));
+            }
+        }
+    }
+
+    // PICK: the leftmost job of the timeline
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        let (_, idx) = self.timeline.pop_first()?;
+        self.timeline_weight -= self.weights[idx];
+        self.wakeup = None;
+
+        let runnable = self.timeline.len() as u32 + 1;
+        let total_weight = self.timeline_weight + self.weights[idx];
+        let slice = self.slice(self.weights[idx], runnable, total_weight);
+
+        let detail = match ctx.logging() {
+            true => {
+                let mut runnable = vec![idx];
+                runnable.extend(self.timeline.iter().map(|&(_, x)| x));
+                format!("{} | slice {}", self.vruntime_detail(ctx, &runnable), slice)
+            }
+            false => String::new(),
+        };
+
+        Some(Pick::new(idx).slice(slice).detail(detail))
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        self.timeline.iter().map(|&(_, x)| x).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.timeline.len()
+    }
+
+    // SCHEDULE: charge the weighted time tick by tick
+    fn ran(&mut self, job: usize, ticks: u32, ctx: &mut Context) {
+        let step = vruntime_of(1, self.weights[job]);
+
+        if ctx.logging() {
+            let mut runnable = vec![job];
+            runnable.extend(self.timeline.iter().map(|&(_, x)| x));
+            let queue = self.queue(ctx.cpu);
+
+            for tick in 0..ticks {
+                self.vruntimes[job] += step;
+                let detail = self.vruntime_detail(ctx, &runnable);
+                ctx.log_tick(ctx.now - ticks + tick, job, &queue, detail);
+            }
+        } else {
+            self.vruntimes[job] += step * ticks as u64;
+        }
+
+        let leftmost = self
+            .timeline
+            .first()
+            .map(|&(vruntime, _)| vruntime)
+            .unwrap_or(u64::MAX);
+        self.min_vruntime = self.min_vruntime.max(self.vruntimes[job].min(leftmost));
+    }
+
+    // SLICE USED UP: back to the timeline
+    fn expired(&mut self, job: usize, _ctx: &mut Context) {
+        self.insert(job);
+    }
+
+    fn preempts(&mut self, _job: usize, _ctx: &mut Context) -> Option<String> {
+        self.wakeup.take()
+    }
+
+    fn preempted(&mut self, job: usize, _ctx: &mut Context) {
+        self.insert(job);
+    }
+
+    fn done(&mut self, _job: usize, _ctx: &mut Context) {
+        self.wakeup = None;
+    }
+}
+
+impl Scheduler for CfsScheduler {
+    fn new(mut jobs: Vec<Job>) -> CfsScheduler {
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        CfsScheduler {
+            jobs,
+            latency: 6,
+            min_granularity: 1,
+            start_debit: false,
+        }
+    }
+
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("CFS（完全公平调度）");
+
+        let mut timeline = CfsTimeline {
+            latency: self.latency,
+            min_granularity: self.min_granularity,
+            start_debit: self.start_debit,
+            weights: self
+                .jobs
+                .iter()
+                .map(|job| nice_to_weight(job.nice))
+                .collect(),
+            vruntimes: vec![0; self.jobs.len()],
+            timeline: BTreeSet::new(),
+            timeline_weight: 0,
+            min_vruntime: 0,
+            wakeup: None,
+        };
+        Engine::new(&mut self.jobs, config).run(&mut timeline, &mut schedule);
+
+        schedule.note(format!(
+            "latency: {}, min granularity: {}, start debit: {}",
+            self.latency, self.min_granularity, self.start_debit
+        ));
+
+        for job in &self.jobs {
+            schedule.note(format!(
+                "{}: nice {}, weight {}",
+                job.name,
+                job.nice,
+                nice_to_weight(job.nice)
+            ));
+        }
+
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_jobs, cfs::CfsScheduler, Job, Scheduler, SchedulerConfig, Segment};
+
+    #[test]
+    fn test() {
+        let jobs: Vec<Job> = build_jobs();
+
+        let mut scheduler = CfsScheduler::new(jobs);
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+        assert!(scheduler.jobs.iter().all(|job| job.served_time == job.service_time));
+        // the running job's vruntime grows by 1 every tick at nice 0
+        let ticks = schedule
+            .events
+            .iter()
+            .filter(|process| {
+                process.status == "TICK" && process.job.as_ref().unwrap().name == "A"
+            })
+            .map(|process| process.detail.as_deref().unwrap())
+            .take(2)
+            .collect::<Vec<&str>>();
+        assert_eq!(ticks, vec!["VRUNTIME: A=1.00", "VRUNTIME: A=2.00 B=1.00"]);
+
+        // nice 0 against nice 5 is about 1024 : 335, so 3 : 1
+        let jobs: Vec<Job> = vec![
+            Job::new("A", 0, 40),
+            Job { nice: 5, ..Job::new("B", 0, 40) },
+        ];
+
+        let mut scheduler = CfsScheduler::new(jobs).latency(8);
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+
+        let first_finish = schedule.jobs[0].finish_time;
+        let b_served = schedule
+            .segments
+            .iter()
+            .take_while(|segment| segment.end <= first_finish)
+            .filter(|segment| segment.job.as_deref() == Some("B"))
+            .map(Segment::ticks)
+            .sum::<u32>();
+        assert_eq!(scheduler.jobs[0].finish_time, Some(40 + b_served));
+        assert!((10..=16).contains(&b_served), "B ran {} ticks", b_served);
+    }
+}
@@ -0,0 +1,497 @@
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use scheduler::{
+    cfs::CfsScheduler,
+    compare::Comparison,
+    dag::ListScheduler,
+    fair::FairShareScheduler,
+    fcfs::FcfsScheduler,
+    gantt::Gantt,
+    hrrn::HrrnScheduler,
+    loader,
+    lottery::LotteryScheduler,
+    mlfq::MlfqScheduler,
+    mlq::{MlqScheduler, QueuePolicy},
+    priority::{PriorityMode, PriorityScheduler},
+    realtime::{RealtimePolicy, RealtimeScheduler},
+    report,
+    rr::{RrScheduler, RrVariant},
+    sjf::SjfScheduler,
+    smp::{self, SmpScheduler},
+    srtf::SrtfScheduler,
+    stride::{StrideScheduler, STRIDE1},
+    workload::{self, Arrivals, ServiceTimes, Workload},
+    Aging, Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+/// Operating system scheduling algorithms, run without a command for the menu.
+#[derive(Parser)]
+#[command(name = "scheduler", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run policies on a job file and print their schedules.
+    Run(RunArgs),
+    /// Run policies on the same jobs and print a table of their averages.
+    Compare(CompareArgs),
+    /// Write a random workload as CSV.
+    Generate(GenerateArgs),
+    /// The interactive menu.
+    Interactive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Algorithm {
+    Fcfs,
+    Sjf,
+    Rr,
+    RrMedian,
+    RrMean,
+    RrPriority,
+    Vrr,
+    Srtf,
+    Priority,
+    PriorityPreemptive,
+    Hrrn,
+    Mlfq,
+    Mlq,
+    Lottery,
+    Stride,
+    Cfs,
+    Smp,
+    /// The longest chain of dependent jobs first.
+    List,
+    /// The CPU divided between the owners of the jobs by their weights.
+    FairShare,
+    /// Earliest deadline first over the periodic tasks instead of the jobs.
+    Edf,
+    /// Rate monotonic over the periodic tasks instead of the jobs.
+    Rm,
+}
+
+impl Algorithm {
+    /// The scheduler with its default options, the processors and the weights of
+    /// `args` are only used by the policies they are for.
+    fn build(&self, jobs: Vec<Job>, args: &JobArgs) -> Box<dyn Scheduler> {
+        let cpus = args.cpus;
+
+        match self {
+            Algorithm::Fcfs => Box::new(FcfsScheduler::new(jobs)),
+            Algorithm::Sjf => Box::new(SjfScheduler::new(jobs)),
+            Algorithm::Rr => Box::new(RrScheduler::new(jobs)),
+            Algorithm::RrMedian => Box::new(RrScheduler::new(jobs).variant(RrVariant::Median)),
+            Algorithm::RrMean => Box::new(RrScheduler::new(jobs).variant(RrVariant::Mean)),
+            Algorithm::RrPriority => Box::new(RrScheduler::new(jobs).variant(RrVariant::Priority)),
+            Algorithm::Vrr => Box::new(RrScheduler::new(jobs).variant(RrVariant::Virtual)),
+            Algorithm::Srtf => Box::new(SrtfScheduler::new(jobs)),
+            Algorithm::Priority => Box::new(PriorityScheduler::new(jobs)),
+            Algorithm::PriorityPreemptive => {
+                Box::new(PriorityScheduler::new(jobs).mode(PriorityMode::Preemptive))
+            }
+            Algorithm::Hrrn => Box::new(HrrnScheduler::new(jobs)),
+            Algorithm::Mlfq => Box::new(MlfqScheduler::new(jobs)),
+            Algorithm::Mlq => Box::new(MlqScheduler::new(jobs)),
+            Algorithm::Lottery => Box::new(LotteryScheduler::new(jobs)),
+            Algorithm::Stride => Box::new(StrideScheduler::new(jobs)),
+            Algorithm::Cfs => Box::new(CfsScheduler::new(jobs)),
+            Algorithm::Smp => Box::new(SmpScheduler::new(jobs).cpus(cpus as usize)),
+            Algorithm::List => Box::new(ListScheduler::new(jobs).cpus(cpus as usize)),
+            Algorithm::FairShare => Box::new(args.weights.iter().fold(
+                FairShareScheduler::new(jobs).policy(args.group_policy.build(args.group_slice)),
+                |scheduler, (owner, weight)| scheduler.weight(owner, *weight),
+            )),
+            Algorithm::Edf | Algorithm::Rm => unreachable!("periodic tasks are not jobs"),
+        }
+    }
+
+    /// Fails if a job may run on a CPU the SMP or list policy does not have.
+    fn check_affinity(&self, jobs: &[Job], args: &JobArgs) -> Result<(), String> {
+        match self {
+            Algorithm::Smp | Algorithm::List => {
+                smp::check_affinity(jobs, args.cpus as usize).map_err(|why| why.to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn is_realtime(&self) -> bool {
+        matches!(self, Algorithm::Edf | Algorithm::Rm)
+    }
+
+    /// Runs the policy on the jobs, or on the periodic tasks of `args` for EDF and RM.
+    fn schedule(
+        &self,
+        jobs: &[Job],
+        args: &JobArgs,
+        config: &SchedulerConfig,
+    ) -> Result<Schedule, String> {
+        let policy = match self {
+            Algorithm::Edf => RealtimePolicy::Edf,
+            Algorithm::Rm => RealtimePolicy::RateMonotonic,
+            _ => {
+                self.check_affinity(jobs, args)?;
+                return Ok(self.build(jobs.to_vec(), args).run(config));
+            }
+        };
+
+        let tasks = loader::load_tasks(&args.tasks)
+            .map_err(|why| format!("{}: {}", args.tasks.display(), why))?;
+        let mut scheduler = RealtimeScheduler::new(tasks, policy).map_err(|why| why.to_string())?;
+        Ok(scheduler.run(config))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// The event log, the job table and the metrics.
+    Table,
+    Json,
+    Csv,
+    /// A Gantt chart, SVG or HTML when the output ends in `.svg` or `.html`.
+    Gantt,
+    /// Chrome Trace Event Format JSON for a trace viewer such as Perfetto.
+    Trace,
+}
+
+/// The policy inside every group of fair share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroupPolicy {
+    Fcfs,
+    Sjf,
+    Rr,
+}
+
+impl GroupPolicy {
+    fn build(&self, slice_size: u32) -> QueuePolicy {
+        match self {
+            GroupPolicy::Fcfs => QueuePolicy::Fcfs,
+            GroupPolicy::Sjf => QueuePolicy::Sjf,
+            GroupPolicy::Rr => QueuePolicy::Rr(slice_size),
+        }
+    }
+}
+
+/// How `generate` draws the gaps between arrivals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Arrival {
+    /// Exponential gaps.
+    Poisson,
+    /// Gaps uniform between 0 and twice the mean.
+    Uniform,
+}
+
+/// How `generate` draws the service times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Service {
+    /// Exponential with the mean `--mean`.
+    Exponential,
+    /// Uniform from `--min` to `--max`.
+    Uniform,
+    /// `--max` for a `--long-share` of the jobs, `--min` for the others.
+    Bimodal,
+}
+
+/// Where the jobs come from and how the policies are set up.
+#[derive(Args)]
+pub struct JobArgs {
+    /// CSV, JSON or TOML job file.
+    #[arg(short, long, default_value = "./data/scheduler_test.csv")]
+    pub input: PathBuf,
+    /// Policies to run, separated by commas.
+    #[arg(short, long, value_delimiter = ',', default_value = "fcfs")]
+    pub algorithms: Vec<Algorithm>,
+    /// Ticks a job runs before it is preempted.
+    #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub quantum: u32,
+    /// Seed of the randomized policies.
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+    /// Processors of the SMP and list policies.
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..))]
+    pub cpus: u32,
+    /// Ticks a CPU spends switching to another job.
+    #[arg(long, default_value_t = 0)]
+    pub context_switch: u32,
+    /// Ticks a CPU spends dispatching the job it ran last, the context switch if not given.
+    #[arg(long)]
+    pub same_job_switch: Option<u32>,
+    /// Ticks of waiting per priority level a job gains under SJF and priority.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub aging: Option<u32>,
+    /// Most priority levels a job gains by aging.
+    #[arg(long, requires = "aging", default_value_t = u32::MAX, hide_default_value = true)]
+    pub aging_cap: u32,
+    /// Marks jobs that waited longer than this many ticks at a time as starved.
+    #[arg(long)]
+    pub starvation: Option<u32>,
+    /// Weights of the owners under fair share, e.g. `alice=2,bob=1`, 1 if not given.
+    #[arg(long, value_delimiter = ',', value_parser = parse_weight)]
+    pub weights: Vec<(String, u32)>,
+    /// The policy inside every group of fair share.
+    #[arg(long, value_enum, default_value_t = GroupPolicy::Rr)]
+    pub group_policy: GroupPolicy,
+    /// Slice size of the RR group policy.
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..))]
+    pub group_slice: u32,
+    /// Periodic tasks of EDF and RM, `name,period,wcet[,deadline]` rows.
+    #[arg(long, default_value = "./data/periodic_test.csv")]
+    pub tasks: PathBuf,
+}
+
+#[derive(Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub jobs: JobArgs,
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    pub format: Format,
+    /// Write to this file instead of the standard output.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct CompareArgs {
+    #[command(flatten)]
+    pub jobs: JobArgs,
+    /// RR quanta to sweep instead of the quantum, e.g. `1-10` or `2,4,8`.
+    #[arg(long, value_parser = parse_quanta)]
+    pub quanta: Option<Quanta>,
+}
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    #[arg(short = 'n', long, default_value_t = 10)]
+    pub jobs: usize,
+    /// Mean service time over the mean gap between arrivals.
+    #[arg(long, default_value_t = 0.8)]
+    pub load: f64,
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+    #[arg(long, value_enum, default_value_t = Arrival::Poisson)]
+    pub arrivals: Arrival,
+    #[arg(long, value_enum, default_value_t = Service::Exponential)]
+    pub service: Service,
+    /// Mean service time of the exponential distribution.
+    #[arg(long, default_value_t = 5.0)]
+    pub mean: f64,
+    /// Shortest service time of the uniform and the bimodal distribution.
+    #[arg(long, default_value_t = 1)]
+    pub min: u32,
+    /// Longest service time of the uniform and the bimodal distribution.
+    #[arg(long, default_value_t = 10)]
+    pub max: u32,
+    /// Share of the long jobs of the bimodal distribution.
+    #[arg(long, default_value_t = 0.1)]
+    pub long_share: f64,
+    /// Write to this file instead of the standard output.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Quanta(Vec<u32>);
+
+fn parse_quanta(quanta: &str) -> Result<Quanta, String> {
+    let quanta = match quanta.split_once('-') {
+        Some((from, to)) => match (from.trim().parse::<u32>(), to.trim().parse::<u32>()) {
+            (Ok(from), Ok(to)) => Ok((from..=to).collect()),
+            _ => Err(()),
+        },
+        None => quanta
+            .split(',')
+            .map(|x| x.trim().parse::<u32>().map_err(|_| ()))
+            .collect::<Result<Vec<u32>, ()>>(),
+    };
+
+    match quanta {
+        Ok(quanta) if !quanta.is_empty() && !quanta.contains(&0) => Ok(Quanta(quanta)),
+        _ => Err("expected positive quanta like 1-10 or 2,4,8".to_string()),
+    }
+}
+
+fn parse_weight(weight: &str) -> Result<(String, u32), String> {
+    match weight.split_once('=') {
+        Some((owner, weight)) if !owner.trim().is_empty() => match weight.trim().parse::<u32>() {
+            Ok(weight) if weight > 0 && weight as u64 <= STRIDE1 => {
+                Ok((owner.trim().to_string(), weight))
+            }
+            _ => Err(format!(
+                "expected a weight from 1 to {} for {}",
+                STRIDE1,
+                owner.trim()
+            )),
+        },
+        _ => Err("expected owner=weight like alice=2".to_string()),
+    }
+}
+
+impl JobArgs {
+    fn config(&self) -> SchedulerConfig {
+        let config = SchedulerConfig::default()
+            .quantum(self.quantum)
+            .seed(self.seed)
+            .context_switch(self.context_switch);
+        let config = match self.same_job_switch {
+            Some(cost) => config.same_job_switch(cost),
+            None => config,
+        };
+        let config = match self.aging {
+            Some(interval) => config.aging(Aging::new(interval, self.aging_cap)),
+            None => config,
+        };
+
+        match self.starvation {
+            Some(threshold) => config.starvation(threshold),
+            None => config,
+        }
+    }
+
+    fn load(&self) -> Result<Vec<Job>, String> {
+        loader::load(&self.input).map_err(|why| format!("{}: {}", self.input.display(), why))
+    }
+}
+
+/// Writes `text` to `output`, or prints it when there is none.
+fn write(output: &Option<PathBuf>, text: &str) -> Result<(), String> {
+    match output {
+        Some(path) => fs::write(path, text)
+            .map_err(|why| format!("couldn't write {}: {}", path.display(), why)),
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+fn run(args: &RunArgs) -> Result<(), String> {
+    // EDF and RM alone don't need the job file
+    let jobs = match args.jobs.algorithms.iter().all(Algorithm::is_realtime) {
+        true => Vec::new(),
+        false => args.jobs.load()?,
+    };
+    // only the table and the trace read the event log
+    let event_log = matches!(args.format, Format::Table | Format::Trace);
+    let config = args.jobs.config().event_log(event_log);
+
+    let schedules = args
+        .jobs
+        .algorithms
+        .iter()
+        .map(|algorithm| algorithm.schedule(&jobs, &args.jobs, &config))
+        .collect::<Result<Vec<Schedule>, String>>()?;
+
+    let extension = args
+        .output
+        .as_ref()
+        .and_then(|path| path.extension())
+        .and_then(|x| x.to_str());
+
+    let text = match (args.format, extension) {
+        (Format::Table, _) => schedules.iter().map(Schedule::to_string).collect(),
+        (Format::Json, _) => report::json(&schedules),
+        (Format::Csv, _) => report::csv(&schedules),
+        (Format::Trace, _) => report::trace(&schedules),
+        (Format::Gantt, Some("svg" | "html")) if schedules.len() > 1 => {
+            return Err("an SVG or HTML chart takes one algorithm".to_string())
+        }
+        (Format::Gantt, Some("svg")) => Gantt::new(&schedules[0]).svg(),
+        (Format::Gantt, Some("html")) => Gantt::new(&schedules[0]).html(),
+        (Format::Gantt, _) => schedules
+            .iter()
+            .map(|schedule| Gantt::new(schedule).terminal())
+            .collect(),
+    };
+
+    write(&args.output, &text)
+}
+
+fn compare(args: &CompareArgs) -> Result<(), String> {
+    if args.jobs.algorithms.iter().any(Algorithm::is_realtime) {
+        return Err("EDF and RM run periodic tasks, they can't be compared on jobs".to_string());
+    }
+
+    let jobs = args.jobs.load()?;
+    for algorithm in &args.jobs.algorithms {
+        algorithm.check_affinity(&jobs, &args.jobs)?;
+    }
+
+    let config = args.jobs.config().event_log(false);
+    let mut comparison = Comparison::new(jobs);
+
+    for algorithm in &args.jobs.algorithms {
+        let build = |jobs| algorithm.build(jobs, &args.jobs);
+
+        comparison = match (algorithm, &args.quanta) {
+            (Algorithm::Rr, Some(Quanta(quanta))) => {
+                comparison.sweep_quantum(build, quanta.clone(), &config)
+            }
+            _ => comparison.policy(build, &config),
+        };
+    }
+
+    print!("{}", comparison);
+    Ok(())
+}
+
+fn generate(args: &GenerateArgs) -> Result<(), String> {
+    if args.load <= 0.0 {
+        return Err("the load factor must be positive".to_string());
+    }
+
+    let service_times = match args.service {
+        Service::Exponential if args.mean <= 0.0 => {
+            return Err("the mean service time must be positive".to_string())
+        }
+        Service::Exponential => ServiceTimes::Exponential { mean: args.mean },
+        _ if args.min == 0 || args.min > args.max => {
+            return Err("the service times need 0 < --min <= --max".to_string())
+        }
+        Service::Uniform => ServiceTimes::Uniform {
+            min: args.min,
+            max: args.max,
+        },
+        Service::Bimodal if !(0.0..=1.0).contains(&args.long_share) => {
+            return Err("the share of long jobs must be in 0..=1".to_string())
+        }
+        Service::Bimodal => ServiceTimes::Bimodal {
+            short: args.min,
+            long: args.max,
+            long_share: args.long_share,
+        },
+    };
+
+    let jobs = Workload::new(args.jobs)
+        .load(args.load)
+        .seed(args.seed)
+        .arrivals(match args.arrivals {
+            Arrival::Poisson => Arrivals::Poisson,
+            Arrival::Uniform => Arrivals::Uniform,
+        })
+        .service_times(service_times)
+        .generate();
+
+    write(&args.output, &workload::to_csv(&jobs))
+}
+
+/// Runs a command, errors go to the standard error and fail the process.
+pub fn execute(command: &Command) -> ExitCode {
+    let result = match command {
+        Command::Run(args) => run(args),
+        Command::Compare(args) => compare(args),
+        Command::Generate(args) => generate(args),
+        Command::Interactive => unreachable!("the menu runs in main"),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(why) => {
+            eprintln!("error: {}", why);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::fmt;
+
+use crate::{metrics::Metrics, Job, Scheduler, SchedulerConfig};
+
+/// Averages the comparison table shows, lower is better for every one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    WholeTime,
+    WeightWholeTime,
+    WaitingTime,
+    ResponseTime,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 4] = [
+        Metric::WholeTime,
+        Metric::WeightWholeTime,
+        Metric::WaitingTime,
+        Metric::ResponseTime,
+    ];
+
+    /// Average of the metric over the finished jobs.
+    pub fn of(&self, metrics: &Metrics) -> f32 {
+        match self {
+            Metric::WholeTime => metrics.whole_time.mean,
+            Metric::WeightWholeTime => metrics.weight_whole_time.mean,
+            Metric::WaitingTime => metrics.waiting_time.mean,
+            Metric::ResponseTime => metrics.response_time.mean,
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Metric::WholeTime => write!(f, "平均周转时间"),
+            Metric::WeightWholeTime => write!(f, "平均带权周转时间"),
+            Metric::WaitingTime => write!(f, "平均等待时间"),
+            Metric::ResponseTime => write!(f, "平均响应时间"),
+        }
+    }
+}
+
+/// Runs several policies on copies of the same workload and tabulates their averages.
+pub struct Comparison {
+    jobs: Vec<Job>,
+    /// Label of every run and the metrics of its schedule, in the order they ran.
+    rows: Vec<(String, Metrics)>,
+}
+
+impl Comparison {
+    pub fn new(jobs: Vec<Job>) -> Comparison {
+        Comparison {
+            jobs,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Runs the scheduler `build` makes of the workload, labelled with its algorithm.
+    pub fn policy(
+        mut self,
+        build: impl FnOnce(Vec<Job>) -> Box<dyn Scheduler>,
+        config: &SchedulerConfig,
+    ) -> Comparison {
+        let schedule = build(self.jobs.clone()).run(config);
+
+        self.rows
+            .push((schedule.algorithm.clone(), schedule.metrics()));
+        self
+    }
+
+    /// Runs a fresh scheduler for every quantum, labelled with the algorithm and `q=N`.
+    pub fn sweep_quantum(
+        mut self,
+        build: impl Fn(Vec<Job>) -> Box<dyn Scheduler>,
+        quanta: impl IntoIterator<Item = u32>,
+        config: &SchedulerConfig,
+    ) -> Comparison {
+        for quantum in quanta {
+            let config = config.clone().quantum(quantum);
+            let schedule = build(self.jobs.clone()).run(&config);
+
+            self.rows.push((
+                format!("{} q={}", schedule.algorithm, quantum),
+                schedule.metrics(),
+            ));
+        }
+
+        self
+    }
+
+    pub fn rows(&self) -> &[(String, Metrics)] {
+        &self.rows
+    }
+
+    /// Labels of the runs with the lowest value of `metric`, several on a tie.
+    pub fn best(&self, metric: Metric) -> Vec<&str> {
+        let lowest = self
+            .rows
+            .iter()
+            .map(|(_, metrics)| metric.of(metrics))
+            .min_by(|a, b| a.total_cmp(b));
+
+        self.rows
+            .iter()
+            .filter(|(_, metrics)| Some(metric.of(metrics)) == lowest)
+            .map(|(label, _)| label.as_str())
+            .collect()
+    }
+}
+
+/// Terminal columns `text` takes, CJK characters take two.
+fn columns(text: &str) -> usize {
+    text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+fn pad(text: &str, width: usize) -> String {
+    format!(
+        "{}{}",
+        text,
+        " ".repeat(width.saturating_sub(columns(text)))
+    )
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label_columns = self
+            .rows
+            .iter()
+            .map(|(label, _)| columns(label))
+            .max()
+            .unwrap_or(0)
+            .max(8);
+
+        writeln!(f, "\n\n[Comparison of {} policies]", self.rows.len())?;
+        write!(f, "| {} |", pad("策略", label_columns))?;
+        for metric in Metric::ALL {
+            write!(f, " {} |", pad(&metric.to_string(), 16))?;
+        }
+        writeln!(f)?;
+
+        let best = Metric::ALL.map(|metric| self.best(metric));
+
+        for (label, metrics) in &self.rows {
+            write!(f, "| {} |", pad(label, label_columns))?;
+
+            for (metric, best) in Metric::ALL.iter().zip(&best) {
+                let value = format!("{:.2}", metric.of(metrics));
+                let mark = if best.contains(&label.as_str()) {
+                    "*"
+                } else {
+                    ""
+                };
+                write!(f, " {} |", pad(&(value + mark), 16))?;
+            }
+
+            writeln!(f)?;
+        }
+
+        writeln!(f, "* 为该指标最优的策略")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        build_jobs,
+        compare::{Comparison, Metric},
+        fcfs::FcfsScheduler,
+        rr::RrScheduler,
+        sjf::SjfScheduler,
+        Scheduler, SchedulerConfig,
+    };
+
+    #[test]
+    fn test() {
+        let config = SchedulerConfig::default();
+        let comparison = Comparison::new(build_jobs())
+            .policy(|jobs| Box::new(FcfsScheduler::new(jobs)), &config)
+            .policy(|jobs| Box::new(SjfScheduler::new(jobs)), &config)
+            .sweep_quantum(|jobs| Box::new(RrScheduler::new(jobs)), 1..=4, &config);
+        print!("{}", comparison);
+
+        assert_eq!(comparison.rows().len(), 6);
+        assert_eq!(comparison.rows()[3].0, "RR（时间片轮转) q=2");
+        assert_eq!(comparison.rows()[3].1.whole_time.mean, 15.8);
+
+        // SJF turns around 10.2 on average against 13.2 of FCFS
+        assert_eq!(
+            comparison.best(Metric::WholeTime),
+            vec!["SJF（短作业优先）"]
+        );
+        assert!(comparison.to_string().contains("10.20*"));
+        assert!(comparison.best(Metric::ResponseTime)[0].starts_with("RR"));
+    }
+}
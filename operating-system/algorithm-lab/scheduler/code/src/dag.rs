@@ -0,0 +1,410 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+/// Why the predecessors of the jobs do not form a DAG.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DagError {
+    UnknownPredecessor {
+        job: String,
+        predecessor: String,
+    },
+    /// The jobs of a cycle, each one a predecessor of the next and the last one of
+    /// the first.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for DagError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DagError::UnknownPredecessor { job, predecessor } => {
+                write!(f, "{} waits for {}, which is not a job", job, predecessor)
+            }
+            DagError::Cycle(jobs) => {
+                write!(f, "dependency cycle {} -> {}", jobs.join(" -> "), jobs[0])
+            }
+        }
+    }
+}
+
+impl std::error::Error for DagError {}
+
+/// The longest chain of dependent jobs, counted from the arrival of its first job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CriticalPath {
+    pub jobs: Vec<String>,
+    /// When the last job finishes at the earliest, even with a CPU for every job.
+    pub end: u32,
+}
+
+impl fmt::Display for CriticalPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "critical path: {}, ends at {}",
+            self.jobs.join(" -> "),
+            self.end
+        )
+    }
+}
+
+/// The jobs of a run and the predecessors every one waits for, by index. A job is
+/// as long as all of its CPU and I/O bursts together.
+#[derive(Debug, Clone)]
+pub struct Dag {
+    names: Vec<String>,
+    arrivals: Vec<u32>,
+    lengths: Vec<u32>,
+    cpu_time: u64,
+    predecessors: Vec<Vec<usize>>,
+    successors: Vec<Vec<usize>>,
+    /// Every job after its predecessors.
+    order: Vec<usize>,
+}
+
+impl Dag {
+    pub fn new(jobs: &[Job]) -> Result<Dag, DagError> {
+        let index = jobs
+            .iter()
+            .enumerate()
+            .map(|(idx, job)| (job.name.as_str(), idx))
+            .collect::<HashMap<&str, usize>>();
+
+        let mut predecessors = Vec::with_capacity(jobs.len());
+        let mut successors = vec![Vec::new(); jobs.len()];
+
+        for (idx, job) in jobs.iter().enumerate() {
+            let mut before = job
+                .predecessors
+                .iter()
+                .map(|name| {
+                    index
+                        .get(name.as_str())
+                        .copied()
+                        .ok_or(DagError::UnknownPredecessor {
+                            job: job.name.clone(),
+                            predecessor: name.clone(),
+                        })
+                })
+                .collect::<Result<Vec<usize>, DagError>>()?;
+            before.sort();
+            before.dedup();
+
+            for &predecessor in &before {
+                successors[predecessor].push(idx);
+            }
+            predecessors.push(before);
+        }
+
+        // KAHN: a job is next once every predecessor is in the order
+        let mut waiting = predecessors.iter().map(Vec::len).collect::<Vec<usize>>();
+        let mut order = (0..jobs.len())
+            .filter(|&idx| waiting[idx] == 0)
+            .collect::<Vec<usize>>();
+        let mut next = 0;
+
+        while next < order.len() {
+            for &successor in &successors[order[next]] {
+                waiting[successor] -= 1;
+                if waiting[successor] == 0 {
+                    order.push(successor);
+                }
+            }
+            next += 1;
+        }
+
+        if order.len() < jobs.len() {
+            let cycle = cycle(&predecessors, &waiting);
+            return Err(DagError::Cycle(
+                cycle
+                    .into_iter()
+                    .map(|idx| jobs[idx].name.clone())
+                    .collect(),
+            ));
+        }
+
+        let lengths = jobs
+            .iter()
+            .map(|job| match job.bursts.is_empty() {
+                true => job.service_time,
+                false => job.bursts.iter().map(|burst| burst.ticks()).sum(),
+            })
+            .collect();
+
+        Ok(Dag {
+            names: jobs.iter().map(|job| job.name.clone()).collect(),
+            arrivals: jobs.iter().map(|job| job.arrival_time).collect(),
+            lengths,
+            cpu_time: jobs.iter().map(|job| job.service_time as u64).sum(),
+            predecessors,
+            successors,
+            order,
+        })
+    }
+
+    pub fn predecessors(&self, job: usize) -> &[usize] {
+        &self.predecessors[job]
+    }
+
+    pub fn successors(&self, job: usize) -> &[usize] {
+        &self.successors[job]
+    }
+
+    /// Whether any job waits for another.
+    pub fn has_edges(&self) -> bool {
+        self.predecessors.iter().any(|before| !before.is_empty())
+    }
+
+    /// Ticks from the start of every job to the end of the longest chain it starts,
+    /// the job included. List scheduling runs the highest level first.
+    pub fn levels(&self) -> Vec<u32> {
+        let mut levels = self.lengths.clone();
+
+        for &idx in self.order.iter().rev() {
+            let after = self.successors[idx]
+                .iter()
+                .map(|&successor| levels[successor])
+                .max()
+                .unwrap_or(0);
+            levels[idx] = self.lengths[idx] + after;
+        }
+
+        levels
+    }
+
+    /// The chain that ends last when every job starts as soon as it arrived and its
+    /// predecessors finished, `None` without jobs.
+    pub fn critical_path(&self) -> Option<CriticalPath> {
+        // the earliest finish of every job and the predecessor it waited for last
+        let mut finish = vec![0; self.names.len()];
+        let mut waited_for = vec![None; self.names.len()];
+
+        for &idx in &self.order {
+            let mut start = self.arrivals[idx];
+            for &predecessor in &self.predecessors[idx] {
+                if finish[predecessor] > start {
+                    start = finish[predecessor];
+                    waited_for[idx] = Some(predecessor);
+                }
+            }
+            finish[idx] = start + self.lengths[idx];
+        }
+
+        // the first job to finish last, then back along what it waited for
+        let last = (0..finish.len()).rev().max_by_key(|&idx| finish[idx])?;
+        let mut jobs = vec![last];
+        while let Some(predecessor) = waited_for[*jobs.last().unwrap()] {
+            jobs.push(predecessor);
+        }
+
+        Some(CriticalPath {
+            jobs: jobs
+                .into_iter()
+                .rev()
+                .map(|idx| self.names[idx].clone())
+                .collect(),
+            end: finish[last],
+        })
+    }
+
+    /// No schedule on `cpus` CPUs finishes before this: not before the critical path
+    /// ends, nor before the CPUs did all of the CPU work from the first arrival on.
+    pub fn lower_bound(&self, cpus: usize) -> u32 {
+        let path = self.critical_path().map_or(0, |path| path.end);
+        let first = self.arrivals.iter().copied().min().unwrap_or(0);
+        let work = self.cpu_time.div_ceil(cpus as u64) as u32;
+
+        path.max(first + work)
+    }
+}
+
+/// A cycle among the jobs Kahn's algorithm left `waiting`: following predecessors
+/// that still wait from any of them has to come back to one already seen.
+fn cycle(predecessors: &[Vec<usize>], waiting: &[usize]) -> Vec<usize> {
+    let mut path = vec![(0..waiting.len()).find(|&idx| waiting[idx] > 0).unwrap()];
+
+    loop {
+        let job = *path.last().unwrap();
+        let before = predecessors[job]
+            .iter()
+            .copied()
+            .find(|&predecessor| waiting[predecessor] > 0)
+            .unwrap();
+
+        if let Some(pos) = path.iter().position(|&idx| idx == before) {
+            // walked backwards, so the dependency order is the reverse, from the
+            // first job of the cycle
+            let mut cycle = path.split_off(pos);
+            cycle.reverse();
+            let first = (0..cycle.len()).min_by_key(|&pos| cycle[pos]).unwrap();
+            cycle.rotate_left(first);
+            return cycle;
+        }
+        path.push(before);
+    }
+}
+
+pub struct ListScheduler {
+    jobs: Vec<Job>,
+    cpus: usize,
+}
+
+impl ListScheduler {
+    pub fn cpus(mut self, cpus: usize) -> ListScheduler {
+        assert!(cpus > 0, "at least one CPU is needed");
+
+        self.cpus = cpus;
+        self
+    }
+}
+
+/// Ready jobs in the order they became ready, the highest level runs first and to
+/// the end of its CPU burst.
+#[derive(Debug, Clone)]
+struct ListQueue {
+    ready: Vec<usize>,
+    levels: Vec<u32>,
+}
+
+impl Policy for ListQueue {
+    fn enqueue(&mut self, job: usize, _ctx: &mut Context) {
+        self.ready.push(job);
+    }
+
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        // the earliest ready wins ties, hence the reversed scan
+        let (pos, &selected) = self
+            .ready
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, &idx)| ctx.jobs[idx].can_run_on(ctx.cpu))
+            .max_by_key(|(_, &idx)| self.levels[idx])?;
+        self.ready.remove(pos);
+
+        let detail = format!("level {}", self.levels[selected]);
+        Some(Pick::new(selected).detail(detail))
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        let mut queue = self.ready.clone();
+        queue.sort_by_key(|&idx| std::cmp::Reverse(self.levels[idx]));
+        queue
+    }
+
+    fn len(&self) -> usize {
+        self.ready.len()
+    }
+}
+
+impl Scheduler for ListScheduler {
+    fn new(mut jobs: Vec<Job>) -> ListScheduler {
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        ListScheduler { jobs, cpus: 1 }
+    }
+
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("LIST（关键路径优先列表调度）");
+
+        // the engine holds the jobs with predecessors if they do not form a DAG
+        let levels =
+            Dag::new(&self.jobs).map_or_else(|_| vec![0; self.jobs.len()], |dag| dag.levels());
+        let mut queue = ListQueue {
+            ready: Vec::new(),
+            levels,
+        };
+
+        Engine::new(&mut self.jobs, config)
+            .cpus(self.cpus)
+            .run(&mut queue, &mut schedule);
+
+        schedule.note(format!("cpus: {}", self.cpus));
+
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dag::{Dag, DagError, ListScheduler},
+        fcfs::FcfsScheduler,
+        Job, Scheduler, SchedulerConfig,
+    };
+
+    /// A diamond A -> B, C -> D and E on its own.
+    fn pipeline() -> Vec<Job> {
+        let after = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+
+        vec![
+            Job::new("A", 0, 2),
+            Job {
+                predecessors: after(&["A"]),
+                ..Job::new("B", 0, 3)
+            },
+            Job {
+                predecessors: after(&["A"]),
+                ..Job::new("C", 1, 1)
+            },
+            Job {
+                predecessors: after(&["B", "C"]),
+                ..Job::new("D", 0, 2)
+            },
+            Job::new("E", 0, 4),
+        ]
+    }
+
+    #[test]
+    fn test() {
+        let jobs = pipeline();
+        let dag = Dag::new(&jobs).unwrap();
+
+        assert_eq!(dag.levels(), vec![7, 5, 3, 2, 4]);
+        let path = dag.critical_path().unwrap();
+        assert_eq!(path.jobs, vec!["A", "B", "D"]);
+        assert_eq!(path.end, 7);
+        assert_eq!(dag.lower_bound(1), 12);
+        assert_eq!(dag.lower_bound(2), 7);
+
+        // FCFS holds B, C and D back until what they wait for finished
+        let schedule = FcfsScheduler::new(jobs.clone()).run(&SchedulerConfig::default());
+        schedule.print();
+        let finish = |name| schedule.finish_time(name).unwrap();
+        assert_eq!(["A", "E", "B", "C", "D"].map(finish), [2, 6, 9, 10, 12]);
+        assert!(schedule.notes.contains(
+            &"critical path: A -> B -> D, ends at 7, makespan lower bound: 12".to_string()
+        ));
+
+        // the longest chain first on two CPUs meets the bound
+        let schedule = ListScheduler::new(jobs)
+            .cpus(2)
+            .run(&SchedulerConfig::default());
+        schedule.print();
+        assert_eq!(schedule.makespan(), 7);
+
+        let mut cyclic = pipeline();
+        cyclic[0].predecessors = vec!["D".to_string()];
+        assert_eq!(
+            Dag::new(&cyclic).unwrap_err(),
+            DagError::Cycle(vec!["A", "B", "D"].into_iter().map(String::from).collect())
+        );
+        cyclic[0].predecessors = vec!["F".to_string()];
+        assert_eq!(
+            Dag::new(&cyclic).unwrap_err().to_string(),
+            "A waits for F, which is not a job"
+        );
+
+        // a cycle is an error up front, and only holds its jobs when run anyway
+        assert!(ListScheduler::try_new(cyclic.clone()).is_err());
+        let schedule = ListScheduler::new(cyclic).run(&SchedulerConfig::default());
+        assert_eq!(schedule.jobs.len(), 1);
+        assert_eq!(schedule.finish_time("E"), Some(4));
+        assert!(schedule.notes.contains(
+            &"A waits for F, which is not a job, so no job with predecessors runs".to_string()
+        ));
+    }
+}
@@ -0,0 +1,808 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use crate::{
+    burst::BurstReport, dag::Dag, Burst, Job, Schedule, SchedulerConfig, SchedulerProcess,
+};
+
+/// Something that happens at a point in time. Events at the same time are handled
+/// in the order of the variants: a CPU burst that ended frees its CPU first, then
+/// the arrivals and the jobs back from I/O queue up, and only then the job whose
+/// slice ran out, so it goes behind them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum EventKind {
+    /// The CPU burst of the job on `cpu` is over, it finished or blocks for I/O.
+    Completion {
+        cpu: usize,
+        dispatch: u64,
+    },
+    Arrival(usize),
+    /// The I/O device finished the burst of the job.
+    IoDone(usize),
+    QuantumExpiry {
+        cpu: usize,
+        dispatch: u64,
+    },
+    /// Asked for by the policy, e.g. for a priority boost.
+    Timer(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Event {
+    time: u32,
+    kind: EventKind,
+}
+
+/// The job a policy hands to a CPU.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pick {
+    pub job: usize,
+    /// Ticks it runs before its slice expires, `None` until its CPU burst is over.
+    pub slice: Option<u32>,
+    /// Logged with the dispatch, e.g. the ratios the choice was made on.
+    pub detail: Option<String>,
+}
+
+impl Pick {
+    pub fn new(job: usize) -> Pick {
+        Pick {
+            job,
+            slice: None,
+            detail: None,
+        }
+    }
+
+    pub fn slice(mut self, slice: u32) -> Pick {
+        assert!(slice > 0, "slice must be positive");
+
+        self.slice = Some(slice);
+        self
+    }
+
+    pub fn detail(mut self, detail: String) -> Pick {
+        self.detail = Some(detail);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Cpu {
+    job: Option<usize>,
+    /// When `job` was dispatched or its ticks were last settled.
+    since: u32,
+    /// Counts the dispatches, the event of an earlier one is stale.
+    dispatch: u64,
+    /// The job dispatched last, a dispatch of it again may cost less.
+    last: Option<usize>,
+    /// Until then the CPU is switching to `job` instead of running it.
+    switch_end: u32,
+    /// Whether the CPU logged that it went idle.
+    idle: bool,
+}
+
+/// What a policy sees of the run when the engine calls it. `cpu` is the processor
+/// the call is about, 0 for an arrival or a job back from I/O.
+pub struct Context<'a> {
+    pub now: u32,
+    pub cpu: usize,
+    /// Every job of the run, a policy refers to them by index.
+    pub jobs: &'a [Job],
+    remaining: &'a [u32],
+    ready_since: &'a [u32],
+    cpus: &'a [Cpu],
+    schedule: &'a mut Schedule,
+    event_log: bool,
+}
+
+impl Context<'_> {
+    /// Ticks left of the burst `job` is in.
+    pub fn remaining(&self, job: usize) -> u32 {
+        self.remaining[job]
+    }
+
+    /// When `job` last became ready: it arrived, came back from I/O or lost the CPU.
+    pub fn ready_since(&self, job: usize) -> u32 {
+        self.ready_since[job]
+    }
+
+    /// The job running on `cpu`.
+    pub fn running(&self, cpu: usize) -> Option<usize> {
+        self.cpus[cpu].job
+    }
+
+    pub fn cpus(&self) -> usize {
+        self.cpus.len()
+    }
+
+    /// Whether the schedule keeps an event log, a detail is only worth building then.
+    pub fn logging(&self) -> bool {
+        self.event_log
+    }
+
+    /// Logs a decision of the policy, e.g. a demotion, on `cpu` at `now`.
+    pub fn log(
+        &mut self,
+        status: &str,
+        job: Option<usize>,
+        queue: &[usize],
+        detail: Option<String>,
+    ) {
+        if !self.event_log {
+            return;
+        }
+
+        self.schedule.log(SchedulerProcess {
+            job: job.map(|idx| self.jobs[idx].clone()),
+            status: status.to_string(),
+            current_time: self.now,
+            current_queue: queue.iter().map(|&x| self.jobs[x].clone()).collect(),
+            detail,
+            cpu: self.cpu,
+        });
+    }
+
+    /// Logs `job` running on `cpu` in the tick that starts at `time`, before `now`,
+    /// for a policy that shows how its state changes tick by tick.
+    pub fn log_tick(&mut self, time: u32, job: usize, queue: &[usize], detail: String) {
+        let now = self.now;
+        self.now = time;
+        self.log("TICK", Some(job), queue, Some(detail));
+        self.now = now;
+    }
+}
+
+/// Decides which ready job runs. The engine gives it every job that becomes ready
+/// and asks it for one whenever a CPU is free; a job it picked is the engine's
+/// until it comes back through `expired`, `preempted` or `enqueue` after I/O.
+pub trait Policy {
+    /// `job` is ready: it arrived or came back from I/O.
+    fn enqueue(&mut self, job: usize, ctx: &mut Context);
+
+    /// Takes the job `ctx.cpu` runs next, `None` leaves the CPU idle.
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick>;
+
+    /// Ready jobs waiting for `cpu`, in the order the policy would pick them.
+    fn queue(&self, cpu: usize) -> Vec<usize>;
+
+    /// Number of ready jobs waiting for any CPU.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `job` ran `ticks` on `ctx.cpu` until `ctx.now`.
+    fn ran(&mut self, _job: usize, _ticks: u32, _ctx: &mut Context) {}
+
+    /// The slice of `job` ran out before its CPU burst did.
+    fn expired(&mut self, job: usize, ctx: &mut Context) {
+        self.enqueue(job, ctx)
+    }
+
+    /// Whether `job`, running on `ctx.cpu`, has to give it up now, with the reason.
+    fn preempts(&mut self, _job: usize, _ctx: &mut Context) -> Option<String> {
+        None
+    }
+
+    /// `job` lost its CPU because of `preempts`.
+    fn preempted(&mut self, job: usize, ctx: &mut Context) {
+        self.enqueue(job, ctx)
+    }
+
+    /// The CPU burst of `job` is over, it finished or blocks for I/O.
+    fn done(&mut self, _job: usize, _ctx: &mut Context) {}
+
+    /// When `timer` should be called next, after `ctx.now`.
+    fn next_timer(&self, _ctx: &Context) -> Option<u32> {
+        None
+    }
+
+    fn timer(&mut self, _ctx: &mut Context) {}
+}
+
+/// Runs jobs from event to event instead of tick by tick, so a run costs the number
+/// of events whatever the ticks in between. Every CPU burst runs on one of `cpus`
+/// processors and every I/O burst on one device serving the blocked jobs in the
+/// order they blocked. A dispatch first spends the context switch of the config. A
+/// job that arrives before its predecessors finished is held back until they did.
+pub struct Engine<'a> {
+    jobs: &'a mut [Job],
+    cpus: usize,
+    event_log: bool,
+    /// Ticks of a dispatch of another job and of the job the CPU ran last.
+    switch_cost: (u32, u32),
+    starvation: Option<u32>,
+}
+
+impl<'a> Engine<'a> {
+    /// `jobs` must be sorted by arrival and finish by tick `u32::MAX`, see
+    /// `loader::parse`. Their finish times are filled in. If their predecessors do
+    /// not form a DAG, no job with predecessors runs.
+    pub fn new(jobs: &'a mut [Job], config: &SchedulerConfig) -> Engine<'a> {
+        Engine {
+            jobs,
+            cpus: 1,
+            event_log: config.event_log,
+            switch_cost: (config.switch_cost(false), config.switch_cost(true)),
+            starvation: config.starvation,
+        }
+    }
+
+    pub fn cpus(mut self, cpus: usize) -> Engine<'a> {
+        assert!(cpus > 0, "at least one CPU is needed");
+
+        self.cpus = cpus;
+        self
+    }
+
+    /// Runs until every job finished or the policy leaves the rest waiting for good.
+    pub fn run(self, policy: &mut dyn Policy, schedule: &mut Schedule) -> BurstReport {
+        schedule.starvation = self.starvation;
+        let mut run = Run::new(self, schedule);
+
+        while let Some(now) = run.next_time() {
+            run.settle(policy, now);
+
+            while let Some(event) = run.pop(now) {
+                run.handle(policy, event);
+            }
+
+            run.preempt(policy, now);
+            run.dispatch(policy, now);
+            run.start_io(now);
+            run.arm(policy, now);
+
+            if run.is_over() {
+                break;
+            }
+
+            run.log_idle(policy, now);
+        }
+
+        if let Some(path) = run.dag.as_ref().and_then(Dag::critical_path) {
+            let bound = run.dag.as_ref().unwrap().lower_bound(run.cpus.len());
+            run.schedule
+                .note(format!("{}, makespan lower bound: {}", path, bound));
+        }
+
+        run.report
+    }
+}
+
+/// The state of one `Engine::run`.
+struct Run<'a> {
+    jobs: &'a mut [Job],
+    schedule: &'a mut Schedule,
+    event_log: bool,
+    switch_cost: (u32, u32),
+    cpus: Vec<Cpu>,
+    // the burst every job is in and the ticks left of it
+    stage: Vec<usize>,
+    remaining: Vec<u32>,
+    ready_since: Vec<u32>,
+    io_queue: VecDeque<usize>,
+    io_running: Option<usize>,
+    /// The predecessors of the jobs, `None` if no job has any.
+    dag: Option<Dag>,
+    // the predecessors every job still waits for and whether it arrived meanwhile
+    unfinished: Vec<usize>,
+    held: Vec<bool>,
+    events: BinaryHeap<Reverse<Event>>,
+    /// Events in `events` that are neither stale nor timers.
+    pending: usize,
+    /// The timer of the policy and its generation, an older timer is stale.
+    timer: Option<u32>,
+    timer_count: u64,
+    report: BurstReport,
+}
+
+/// The burst `stage` of `job`, a job without bursts has one CPU burst.
+fn burst(job: &Job, stage: usize) -> Burst {
+    match job.bursts.is_empty() {
+        true => Burst::Cpu(job.service_time),
+        false => job.bursts[stage],
+    }
+}
+
+impl<'a> Run<'a> {
+    fn new(engine: Engine<'a>, schedule: &'a mut Schedule) -> Run<'a> {
+        let task_len = engine.jobs.len();
+        let events = engine
+            .jobs
+            .iter()
+            .enumerate()
+            .map(|(idx, job)| {
+                Reverse(Event {
+                    time: job.arrival_time,
+                    kind: EventKind::Arrival(idx),
+                })
+            })
+            .collect();
+        let remaining = engine
+            .jobs
+            .iter()
+            .map(|job| burst(job, 0).ticks())
+            .collect();
+
+        // without a DAG the jobs with predecessors are held for good
+        let dag = match engine.jobs.iter().any(|job| !job.predecessors.is_empty()) {
+            true => Dag::new(engine.jobs)
+                .map_err(|why| schedule.note(format!("{}, so no job with predecessors runs", why)))
+                .ok(),
+            false => None,
+        };
+        let unfinished = engine
+            .jobs
+            .iter()
+            .enumerate()
+            .map(|(idx, job)| match &dag {
+                Some(dag) => dag.predecessors(idx).len(),
+                None => job.predecessors.len(),
+            })
+            .collect();
+
+        Run {
+            jobs: engine.jobs,
+            schedule,
+            event_log: engine.event_log,
+            switch_cost: engine.switch_cost,
+            cpus: vec![Cpu::default(); engine.cpus],
+            stage: vec![0; task_len],
+            remaining,
+            ready_since: vec![0; task_len],
+            io_queue: VecDeque::new(),
+            io_running: None,
+            dag,
+            unfinished,
+            held: vec![false; task_len],
+            events,
+            pending: task_len,
+            timer: None,
+            timer_count: 0,
+            report: BurstReport::default(),
+        }
+    }
+
+    fn context(&mut self, now: u32, cpu: usize) -> Context<'_> {
+        Context {
+            now,
+            cpu,
+            jobs: self.jobs,
+            remaining: &self.remaining,
+            ready_since: &self.ready_since,
+            cpus: &self.cpus,
+            schedule: self.schedule,
+            event_log: self.event_log,
+        }
+    }
+
+    fn push(&mut self, time: u32, kind: EventKind) {
+        if !matches!(kind, EventKind::Timer(..)) {
+            self.pending += 1;
+        }
+
+        self.events.push(Reverse(Event { time, kind }));
+    }
+
+    fn is_stale(&self, event: &Event) -> bool {
+        match event.kind {
+            EventKind::Completion { cpu, dispatch }
+            | EventKind::QuantumExpiry { cpu, dispatch } => {
+                self.cpus[cpu].dispatch != dispatch || self.cpus[cpu].job.is_none()
+            }
+            EventKind::Timer(count) => self.timer.is_none() || self.timer_count != count,
+            _ => false,
+        }
+    }
+
+    /// Time of the next event that still matters.
+    fn next_time(&mut self) -> Option<u32> {
+        while let Some(Reverse(event)) = self.events.peek() {
+            if !self.is_stale(event) {
+                return Some(event.time);
+            }
+            self.events.pop();
+        }
+
+        None
+    }
+
+    fn pop(&mut self, now: u32) -> Option<Event> {
+        while let Some(Reverse(event)) = self.events.peek().copied() {
+            if event.time != now {
+                return None;
+            }
+
+            self.events.pop();
+            if !self.is_stale(&event) {
+                if !matches!(event.kind, EventKind::Timer(..)) {
+                    self.pending -= 1;
+                }
+                return Some(event);
+            }
+        }
+
+        None
+    }
+
+    /// Logs an event of the engine with the queue of `cpu`.
+    fn log(
+        &mut self,
+        policy: &dyn Policy,
+        now: u32,
+        cpu: usize,
+        status: &str,
+        job: Option<usize>,
+        detail: Option<String>,
+    ) {
+        if !self.event_log {
+            return;
+        }
+
+        let current_queue = policy
+            .queue(cpu)
+            .iter()
+            .map(|&x| self.jobs[x].clone())
+            .collect::<Vec<Job>>();
+
+        self.schedule.log(SchedulerProcess {
+            job: job.map(|idx| self.jobs[idx].clone()),
+            status: status.to_string(),
+            current_time: now,
+            current_queue,
+            detail,
+            cpu,
+        });
+    }
+
+    /// Charges the running jobs for the ticks since they were last settled.
+    fn settle(&mut self, policy: &mut dyn Policy, now: u32) {
+        for cpu in 0..self.cpus.len() {
+            let Some(idx) = self.cpus[cpu].job else {
+                continue;
+            };
+
+            // SWITCH: the first ticks of a dispatch are overhead
+            let since = self.cpus[cpu].since;
+            let switching = now.min(self.cpus[cpu].switch_end).saturating_sub(since);
+            if switching > 0 {
+                self.schedule
+                    .switched(&self.jobs[idx].name, cpu, since, switching);
+                self.cpus[cpu].since += switching;
+            }
+
+            let ticks = now - self.cpus[cpu].since;
+            if ticks == 0 {
+                continue;
+            }
+
+            self.schedule
+                .ran(&self.jobs[idx].name, cpu, self.cpus[cpu].since, ticks);
+            self.jobs[idx].served_time += ticks;
+            self.remaining[idx] -= ticks;
+            self.cpus[cpu].since = now;
+            self.report.cpu_busy += ticks;
+
+            policy.ran(idx, ticks, &mut self.context(now, cpu));
+        }
+    }
+
+    /// Takes the job off `cpu`, its pending event turns stale.
+    fn stop(&mut self, cpu: usize) -> usize {
+        let idx = self.cpus[cpu].job.take().unwrap();
+        self.cpus[cpu].dispatch += 1;
+        idx
+    }
+
+    fn handle(&mut self, policy: &mut dyn Policy, event: Event) {
+        let now = event.time;
+
+        match event.kind {
+            EventKind::Completion { cpu, .. } => {
+                let idx = self.stop(cpu);
+                let task_len = self.jobs[idx].bursts.len().max(1);
+
+                if self.stage[idx] + 1 == task_len {
+                    // FINISHED: has finished its job
+                    let current_task = &mut self.jobs[idx];
+                    current_task.finish_time = Some(now);
+                    current_task.whole_time = Some(now - current_task.arrival_time);
+                    current_task.weight_whole_time = Some(
+                        current_task.whole_time.unwrap() as f32 / current_task.service_time as f32,
+                    );
+                    self.report.makespan = self.report.makespan.max(now);
+
+                    match self.event_log {
+                        true => self.log(policy, now, cpu, "FINISHED", Some(idx), None),
+                        false => self.schedule.finish(&self.jobs[idx]),
+                    }
+                } else {
+                    // BLOCKED: the CPU burst is over, wait for the I/O device
+                    self.stage[idx] += 1;
+                    self.remaining[idx] = burst(&self.jobs[idx], self.stage[idx]).ticks();
+                    self.io_queue.push_back(idx);
+
+                    let detail = format!("I/O {} ticks", self.remaining[idx]);
+                    self.log(policy, now, cpu, "BLOCKED", Some(idx), Some(detail));
+                }
+
+                policy.done(idx, &mut self.context(now, cpu));
+
+                if self.jobs[idx].finish_time.is_some() {
+                    self.release(policy, now, idx);
+                }
+            }
+            EventKind::Arrival(idx) if self.unfinished[idx] > 0 => {
+                // HELD: not ready before its predecessors finished
+                self.held[idx] = true;
+
+                let detail = self.event_log.then(|| {
+                    let unfinished = self.predecessor_names(idx, |job| job.finish_time.is_none());
+                    format!("waits for {}", unfinished)
+                });
+                self.log(policy, now, 0, "HELD", Some(idx), detail);
+            }
+            EventKind::Arrival(idx) => {
+                // ENQUEUE
+                self.ready_since[idx] = now;
+                policy.enqueue(idx, &mut self.context(now, 0));
+            }
+            EventKind::IoDone(idx) => {
+                // READY: back from the I/O device
+                self.io_running = None;
+                self.stage[idx] += 1;
+                self.remaining[idx] = burst(&self.jobs[idx], self.stage[idx]).ticks();
+                self.ready_since[idx] = now;
+
+                policy.enqueue(idx, &mut self.context(now, 0));
+                self.log(
+                    policy,
+                    now,
+                    0,
+                    "READY",
+                    Some(idx),
+                    Some("I/O done".to_string()),
+                );
+            }
+            EventKind::QuantumExpiry { cpu, .. } => {
+                // FORCE MOVE TO NEXT TASK
+                let idx = self.stop(cpu);
+                self.ready_since[idx] = now;
+                policy.expired(idx, &mut self.context(now, cpu));
+            }
+            EventKind::Timer(..) => {
+                self.timer = None;
+                policy.timer(&mut self.context(now, 0));
+            }
+        }
+    }
+
+    /// Readies the held jobs that waited for `idx` last, it just finished.
+    fn release(&mut self, policy: &mut dyn Policy, now: u32, idx: usize) {
+        let Some(dag) = &self.dag else {
+            return;
+        };
+
+        for successor in dag.successors(idx).to_vec() {
+            self.unfinished[successor] -= 1;
+            if self.unfinished[successor] > 0 || !self.held[successor] {
+                continue;
+            }
+
+            // READY: every predecessor has finished
+            self.held[successor] = false;
+            self.ready_since[successor] = now;
+            policy.enqueue(successor, &mut self.context(now, 0));
+
+            let detail = self
+                .event_log
+                .then(|| format!("after {}", self.predecessor_names(successor, |_| true)));
+            self.log(policy, now, 0, "READY", Some(successor), detail);
+        }
+    }
+
+    /// The predecessors of `idx` that match `filter`, separated by commas.
+    fn predecessor_names(&self, idx: usize, filter: impl Fn(&Job) -> bool) -> String {
+        let Some(dag) = &self.dag else {
+            return self.jobs[idx].predecessors.join(", ");
+        };
+
+        dag.predecessors(idx)
+            .iter()
+            .map(|&predecessor| &self.jobs[predecessor])
+            .filter(|job| filter(job))
+            .map(|job| job.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+
+    fn preempt(&mut self, policy: &mut dyn Policy, now: u32) {
+        for cpu in 0..self.cpus.len() {
+            let Some(idx) = self.cpus[cpu].job else {
+                continue;
+            };
+
+            if let Some(reason) = policy.preempts(idx, &mut self.context(now, cpu)) {
+                // its completion or expiry will not come
+                self.stop(cpu);
+                self.pending -= 1;
+                self.ready_since[idx] = now;
+
+                policy.preempted(idx, &mut self.context(now, cpu));
+                let detail = Some(reason).filter(|reason| !reason.is_empty());
+                self.log(policy, now, cpu, "PREEMPTED", Some(idx), detail);
+            }
+        }
+    }
+
+    // SCHEDULE
+    fn dispatch(&mut self, policy: &mut dyn Policy, now: u32) {
+        for cpu in 0..self.cpus.len() {
+            if self.cpus[cpu].job.is_some() {
+                continue;
+            }
+
+            let Some(pick) = policy.pick(&mut self.context(now, cpu)) else {
+                continue;
+            };
+
+            let idx = pick.job;
+            let waited = now - self.ready_since[idx];
+            self.jobs[idx].max_wait = self.jobs[idx].max_wait.max(waited);
+
+            let dispatch = self.cpus[cpu].dispatch + 1;
+            let cost = match self.cpus[cpu].last == Some(idx) {
+                true => self.switch_cost.1,
+                false => self.switch_cost.0,
+            };
+            self.cpus[cpu] = Cpu {
+                job: Some(idx),
+                since: now,
+                dispatch,
+                last: Some(idx),
+                switch_end: now + cost,
+                idle: false,
+            };
+
+            // the slice starts once the switch is over
+            let start = now + cost;
+            let burst_end = start + self.remaining[idx];
+            match pick.slice {
+                Some(slice) if slice < self.remaining[idx] => {
+                    self.push(start + slice, EventKind::QuantumExpiry { cpu, dispatch })
+                }
+                _ => self.push(burst_end, EventKind::Completion { cpu, dispatch }),
+            }
+
+            if cost > 0 {
+                let detail = format!("{} ticks", cost);
+                self.log(policy, now, cpu, "SWITCH", Some(idx), Some(detail));
+            }
+            self.log(policy, now, cpu, "RUNNING", Some(idx), pick.detail);
+        }
+    }
+
+    // I/O: one request at a time
+    fn start_io(&mut self, now: u32) {
+        if self.io_running.is_some() {
+            return;
+        }
+
+        if let Some(idx) = self.io_queue.pop_front() {
+            self.io_running = Some(idx);
+            self.report.io_busy += self.remaining[idx];
+            self.push(now + self.remaining[idx], EventKind::IoDone(idx));
+        }
+    }
+
+    /// Sets the timer to what the policy wants next.
+    fn arm(&mut self, policy: &mut dyn Policy, now: u32) {
+        let next = policy.next_timer(&self.context(now, 0));
+        if next == self.timer {
+            return;
+        }
+
+        self.timer = next;
+        self.timer_count += 1;
+
+        if let Some(time) = next {
+            assert!(time > now, "a timer has to be in the future");
+            self.push(time, EventKind::Timer(self.timer_count));
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        self.pending == 0
+            && self.timer.is_none()
+            && self.io_running.is_none()
+            && self.cpus.iter().all(|cpu| cpu.job.is_none())
+    }
+
+    // IDLE: every job is blocked, held, has not arrived or cannot run here
+    fn log_idle(&mut self, policy: &dyn Policy, now: u32) {
+        for cpu in 0..self.cpus.len() {
+            if self.cpus[cpu].job.is_some() || self.cpus[cpu].idle {
+                continue;
+            }
+
+            self.cpus[cpu].idle = true;
+
+            let detail = self
+                .io_running
+                .map(|idx| format!("I/O: {}", self.jobs[idx].name));
+            self.log(policy, now, cpu, "WAITING", None, detail);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fcfs::FcfsQueue, rr::RrScheduler, workload::Workload, Job, Schedule, Scheduler,
+        SchedulerConfig,
+    };
+
+    use super::Engine;
+
+    #[test]
+    fn test() {
+        // a hundred thousand jobs cost their events, not the ticks they span
+        let mut jobs = Workload::new(100_000).seed(7).generate();
+        let config = SchedulerConfig::default().event_log(false);
+
+        let mut schedule = Schedule::new("FCFS");
+        let report = Engine::new(&mut jobs, &config).run(&mut FcfsQueue::default(), &mut schedule);
+
+        assert_eq!(schedule.jobs.len(), jobs.len());
+        assert!(schedule.events.is_empty());
+        assert_eq!(
+            report.cpu_busy,
+            jobs.iter().map(|job| job.service_time).sum::<u32>()
+        );
+        assert_eq!(report.makespan, schedule.makespan());
+
+        // switching to A costs 2 ticks, dispatching A again after its slice 1
+        let config = SchedulerConfig::default()
+            .quantum(2)
+            .context_switch(2)
+            .same_job_switch(1);
+        let schedule = RrScheduler::new(vec![Job::new("A", 0, 4)]).run(&config);
+        schedule.print();
+
+        let overhead = schedule
+            .segments
+            .iter()
+            .filter(|segment| segment.overhead)
+            .map(|segment| (segment.start, segment.end))
+            .collect::<Vec<(u32, u32)>>();
+        assert_eq!(overhead, vec![(0, 2), (4, 5)]);
+        assert_eq!(schedule.jobs[0].finish_time, 7);
+
+        let metrics = schedule.metrics();
+        assert_eq!((metrics.busy, metrics.overhead), (4, 3));
+        assert_eq!(metrics.utilization, 1.0);
+        assert_eq!(metrics.effective_utilization, 4.0 / 7.0);
+    }
+
+    /// The full 10^6 jobs, slow in a debug build: `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn million() {
+        let jobs = Workload::new(1_000_000).seed(7).generate();
+        let service = jobs.iter().map(|job| job.service_time).sum::<u32>();
+        let config = SchedulerConfig::default().event_log(false);
+
+        let mut fcfs = jobs.clone();
+        let mut schedule = Schedule::new("FCFS");
+        let report = Engine::new(&mut fcfs, &config).run(&mut FcfsQueue::default(), &mut schedule);
+        assert_eq!(schedule.jobs.len(), jobs.len());
+        assert_eq!(report.cpu_busy, service);
+
+        // RR dispatches every job once per slice, far more events than FCFS
+        let schedule = RrScheduler::new(jobs).run(&config.quantum(2));
+        assert_eq!(schedule.jobs.len(), 1_000_000);
+        assert_eq!(schedule.metrics().busy, service);
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    mlq::QueuePolicy,
+    share::ShareReport,
+    stride::STRIDE1,
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+/// Group of the jobs without an owner.
+const NO_OWNER: &str = "-";
+
+/// Divides the CPU between the owners of the jobs by their weights, then between
+/// the jobs of an owner by the FCFS, SJF or RR queue of its policy.
+pub struct FairShareScheduler {
+    jobs: Vec<Job>,
+    policy: QueuePolicy,
+    /// Weights by owner, 1 for an owner without one.
+    weights: HashMap<String, u32>,
+    share: ShareReport,
+}
+
+impl FairShareScheduler {
+    /// The policy inside every group.
+    pub fn policy(mut self, policy: QueuePolicy) -> FairShareScheduler {
+        if let QueuePolicy::Rr(slice_size) = policy {
+            assert!(slice_size > 0, "slice size must be positive");
+        }
+
+        self.policy = policy;
+        self
+    }
+
+    /// Gives the jobs of `owner` `weight` shares of the CPU, `-` names the jobs
+    /// without an owner. The weight is at most `STRIDE1`, a group's stride is
+    /// `STRIDE1 / weight`.
+    pub fn weight(mut self, owner: &str, weight: u32) -> FairShareScheduler {
+        assert!(weight > 0, "weight must be positive");
+        assert!(
+            weight as u64 <= STRIDE1,
+            "weight must be at most {}",
+            STRIDE1
+        );
+
+        self.weights.insert(owner.to_string(), weight);
+        self
+    }
+
+    /// Owners in the order their first job arrived, with their weights.
+    fn groups(&self) -> (Vec<String>, Vec<u32>) {
+        let mut names: Vec<String> = Vec::new();
+        for job in &self.jobs {
+            let owner = job.owner.as_deref().unwrap_or(NO_OWNER);
+            if !names.iter().any(|name| name == owner) {
+                names.push(owner.to_string());
+            }
+        }
+
+        let weights = names
+            .iter()
+            .map(|name| self.weights.get(name).copied().unwrap_or(1))
+            .collect();
+
+        (names, weights)
+    }
+}
+
+/// One queue per group and a stride pass for every group: the group with the
+/// smallest pass runs for at most a quantum, and its pass grows by its stride every
+/// tick. A job the end of the quantum took off before its slice ran out goes on
+/// first when its group runs again.
+struct FairShareQueues {
+    quantum: u32,
+    group_of: Vec<usize>,
+    names: Vec<String>,
+    queues: Vec<Box<dyn Policy>>,
+    strides: Vec<u64>,
+    passes: Vec<u64>,
+    /// Ready and running jobs of every group, a group competes while it has any.
+    active: Vec<usize>,
+    /// Ticks the running job may run before its group queue wants it back.
+    slice: Option<u32>,
+    ticks: u32,
+    share: ShareReport,
+}
+
+impl Policy for FairShareQueues {
+    // ENQUEUE: a group that starts competing again starts at the smallest pass of
+    // the competing groups, so it can't make up for the time it had nothing to run
+    fn enqueue(&mut self, job: usize, ctx: &mut Context) {
+        let group = self.group_of[job];
+
+        if self.active[group] == 0 {
+            let competing = (0..self.names.len())
+                .filter(|&x| self.active[x] > 0)
+                .map(|x| self.passes[x])
+                .min();
+            if let Some(pass) = competing {
+                self.passes[group] = self.passes[group].max(pass);
+            }
+            self.share.join(group);
+        }
+
+        self.active[group] += 1;
+        self.queues[group].enqueue(job, ctx);
+    }
+
+    // SELECT: the smallest pass of the groups with a ready job
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        let group = (0..self.names.len())
+            .filter(|&x| !self.queues[x].is_empty())
+            .min_by_key(|&x| (self.passes[x], x))?;
+
+        let detail = match ctx.logging() {
+            true => {
+                let pass = (0..self.names.len())
+                    .filter(|&x| self.active[x] > 0)
+                    .map(|x| format!("{}={}", self.names[x], self.passes[x]))
+                    .collect::<Vec<String>>();
+                format!("group {}, PASS: {}", self.names[group], pass.join(" "))
+            }
+            false => String::new(),
+        };
+
+        let pick = self.queues[group].pick(ctx)?;
+        self.slice = pick.slice;
+        self.ticks = 0;
+
+        let slice = pick
+            .slice
+            .map_or(self.quantum, |slice| slice.min(self.quantum));
+        Some(Pick::new(pick.job).slice(slice).detail(detail))
+    }
+
+    fn queue(&self, cpu: usize) -> Vec<usize> {
+        self.queues
+            .iter()
+            .flat_map(|queue| queue.queue(cpu))
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.queues.iter().map(|queue| queue.len()).sum()
+    }
+
+    fn ran(&mut self, job: usize, ticks: u32, ctx: &mut Context) {
+        let group = self.group_of[job];
+
+        self.ticks += ticks;
+        self.passes[group] += self.strides[group] * ticks as u64;
+        self.share.ran(group, ticks);
+        self.queues[group].ran(job, ticks, ctx);
+    }
+
+    fn expired(&mut self, job: usize, ctx: &mut Context) {
+        let group = self.group_of[job];
+
+        match self.slice {
+            Some(slice) if self.ticks >= slice => self.queues[group].expired(job, ctx),
+            // the quantum of the group is over, not the slice
+            _ => self.queues[group].preempted(job, ctx),
+        }
+    }
+
+    // DONE: finished or blocked, a group without ready jobs stops competing
+    fn done(&mut self, job: usize, ctx: &mut Context) {
+        let group = self.group_of[job];
+
+        self.active[group] -= 1;
+        if self.active[group] == 0 {
+            self.share.leave(group);
+        }
+        self.queues[group].done(job, ctx);
+    }
+}
+
+impl Scheduler for FairShareScheduler {
+    fn new(mut jobs: Vec<Job>) -> FairShareScheduler {
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        FairShareScheduler {
+            jobs,
+            policy: QueuePolicy::Rr(2),
+            weights: HashMap::new(),
+            share: ShareReport::groups(Vec::new(), Vec::new()),
+        }
+    }
+
+    /// Decides between the groups every `config.quantum` ticks.
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("FSS（公平份额调度）");
+
+        let (names, weights) = self.groups();
+        let group_of = self
+            .jobs
+            .iter()
+            .map(|job| {
+                let owner = job.owner.as_deref().unwrap_or(NO_OWNER);
+                names.iter().position(|name| name == owner).unwrap()
+            })
+            .collect();
+
+        let mut queues = FairShareQueues {
+            quantum: config.quantum,
+            group_of,
+            queues: names.iter().map(|_| self.policy.build()).collect(),
+            strides: weights
+                .iter()
+                .map(|&weight| STRIDE1 / weight as u64)
+                .collect(),
+            passes: vec![0; names.len()],
+            active: vec![0; names.len()],
+            slice: None,
+            ticks: 0,
+            share: ShareReport::groups(names.clone(), weights),
+            names,
+        };
+        Engine::new(&mut self.jobs, config).run(&mut queues, &mut schedule);
+        self.share = queues.share;
+
+        schedule.note(format!(
+            "quantum: {}, policy: {:?}",
+            config.quantum, self.policy
+        ));
+        schedule.note(self.share.to_string());
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fair::FairShareScheduler, rr::RrScheduler, Job, Scheduler, SchedulerConfig};
+
+    #[test]
+    fn test() {
+        let owned = |name, owner: &str| Job {
+            owner: Some(owner.to_string()),
+            ..Job::new(name, 0, 12)
+        };
+        let jobs = vec![
+            owned("A", "alice"),
+            owned("B", "alice"),
+            owned("C", "bob"),
+            Job::new("D", 40, 2),
+        ];
+
+        let mut scheduler = FairShareScheduler::new(jobs.clone()).weight("alice", 3);
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+
+        // alice gets 3 of every 4 ticks however many jobs she has
+        let first_ticks = schedule
+            .segments
+            .iter()
+            .flat_map(|segment| vec![segment.job.as_deref().unwrap(); segment.ticks() as usize])
+            .take(8)
+            .collect::<Vec<&str>>();
+        assert_eq!(first_ticks, vec!["A", "C", "A", "B", "B", "C", "A", "A"]);
+        assert_eq!(schedule.finish_time("C"), Some(36));
+
+        for group in 0..3 {
+            let ratio = scheduler.share.received(group) as f32 / scheduler.share.entitled(group);
+            assert!(
+                (0.8..1.25).contains(&ratio),
+                "group {} got {}",
+                group,
+                ratio
+            );
+        }
+
+        // with equal weights bob gets half of the CPU, by jobs only a third
+        let schedule = FairShareScheduler::new(jobs.clone()).run(&SchedulerConfig::default());
+        assert_eq!(schedule.finish_time("C"), Some(24));
+        let schedule = RrScheduler::new(jobs).run(&SchedulerConfig::default());
+        assert!(schedule.finish_time("C").unwrap() > 30);
+    }
+}
@@ -1,61 +1,79 @@
-use crate::{Job, Scheduler, SchedulerResult, SchedulerProcess};
+use std::collections::VecDeque;
+
+use crate::{
+    burst::BurstReport,
+    engine::{Context, Engine, Pick, Policy},
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
 
 pub struct FcfsScheduler {
-    jobs: Vec<Job>,
-    results: SchedulerResult,
+    pub(crate) jobs: Vec<Job>,
+    /// Set when the jobs had I/O bursts.
+    pub(crate) bursts: Option<BurstReport>,
+}
+
+/// One queue in the order the jobs became ready, a job keeps the CPU for its whole
+/// burst.
+#[derive(Debug, Clone, Default)]
+pub struct FcfsQueue {
+    queue: VecDeque<usize>,
+}
+
+impl Policy for FcfsQueue {
+    fn enqueue(&mut self, job: usize, _ctx: &mut Context) {
+        self.queue.push_back(job);
+    }
+
+    fn pick(&mut self, _ctx: &mut Context) -> Option<Pick> {
+        self.queue.pop_front().map(Pick::new)
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        self.queue.iter().copied().collect()
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Taken off by a higher queue, it goes on first.
+    fn preempted(&mut self, job: usize, _ctx: &mut Context) {
+        self.queue.push_front(job);
+    }
 }
 
 impl Scheduler for FcfsScheduler {
     fn new(mut jobs: Vec<Job>) -> FcfsScheduler {
         jobs.sort_by_key(|job| job.arrival_time);
 
-        FcfsScheduler {
-            jobs: jobs,
-            results: SchedulerResult::new(),
-        }
+        FcfsScheduler { jobs, bursts: None }
     }
 
-    fn run(&mut self) {
-        let mut current_time: u32 = 0;
-
-        for current_job in self.jobs.iter_mut() {
-            if current_time < current_job.arrival_time {
-                current_time = current_job.arrival_time;
-            }
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("FCFS（先来先服务）");
 
-            current_job.finish_time = Some(current_time + current_job.service_time);
-            current_job.whole_time =
-                Some(current_job.finish_time.unwrap() - current_job.arrival_time);
-            current_job.weight_whole_time =
-                Some(current_job.whole_time.unwrap() as f32 / current_job.service_time as f32);
+        let bursts =
+            Engine::new(&mut self.jobs, config).run(&mut FcfsQueue::default(), &mut schedule);
 
-            self.results.log(SchedulerProcess {
-                job: Some(current_job.clone()),
-                status: "FINISHED".to_string(),
-                current_time,
-                current_queue: vec![],
-            });
-
-            current_time += current_job.service_time;
+        if self.jobs.iter().any(Job::has_io) {
+            schedule.note(bursts.to_string());
+            self.bursts = Some(bursts);
         }
-    }
 
-    fn print(&mut self) {
-        println!("\n\n[Scheduler algorithm: FCFS（先来先服务）]");
-        self.results.print();
+        schedule
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{build_jobs, fcfs::FcfsScheduler, Job, Scheduler};
+    use crate::{build_jobs, fcfs::FcfsScheduler, Job, Scheduler, SchedulerConfig};
 
     #[test]
     fn test() {
         let jobs: Vec<Job> = build_jobs();
         let mut scheduler = FcfsScheduler::new(jobs);
 
-        scheduler.run();
-        scheduler.print();
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
     }
 }
@@ -0,0 +1,430 @@
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{JobMetrics, Schedule, Segment};
+
+/// Background colors of the jobs in the terminal, black text on top.
+const ANSI_COLORS: [u8; 12] = [41, 42, 43, 44, 45, 46, 101, 102, 103, 104, 105, 106];
+/// Fill of the ticks a CPU spent switching between jobs.
+const SWITCH_COLOR: &str = "#999";
+const SVG_COLORS: [&str; 12] = [
+    "#e15759", "#59a14f", "#edc948", "#4e79a7", "#b07aa1", "#76b7b2", "#ff9da7", "#8cd17d",
+    "#f1ce63", "#a0cbe8", "#d4a6c8", "#86bcb6",
+];
+
+/// Columns of the terminal chart, longer schedules put several ticks in a column.
+const TERMINAL_WIDTH: u32 = 100;
+/// Pixels of the time axis of the SVG chart, at most 24 per tick.
+const SVG_WIDTH: f32 = 1000.0;
+const LANE_HEIGHT: f32 = 32.0;
+const LEFT: f32 = 60.0;
+const TOP: f32 = 40.0;
+
+/// Time axis chart of a schedule, one lane per CPU with the jobs it ran, the gaps it
+/// was idle and a mark wherever it switched from one job straight to another.
+pub struct Gantt {
+    algorithm: String,
+    makespan: u32,
+    /// Busy segments and idle gaps of every CPU in time order.
+    lanes: Vec<Vec<Segment>>,
+    /// Position of every job in the order they first ran, picks its color.
+    colors: HashMap<String, usize>,
+    /// Job names in the order they first ran.
+    names: Vec<String>,
+    jobs: Vec<JobMetrics>,
+}
+
+impl Gantt {
+    pub fn new(schedule: &Schedule) -> Gantt {
+        let mut lanes = vec![Vec::new(); schedule.cpus()];
+        for segment in schedule.segments.iter().chain(&schedule.idle_gaps()) {
+            lanes[segment.cpu].push(segment.clone());
+        }
+
+        for lane in lanes.iter_mut() {
+            lane.sort_by_key(|segment| segment.start);
+        }
+
+        let mut first_runs = schedule.segments.iter().collect::<Vec<&Segment>>();
+        first_runs.sort_by_key(|segment| (segment.start, segment.cpu));
+
+        let mut colors = HashMap::new();
+        let mut names = Vec::new();
+        for name in first_runs.iter().filter_map(|segment| segment.job.as_ref()) {
+            if !colors.contains_key(name) {
+                colors.insert(name.clone(), names.len());
+                names.push(name.clone());
+            }
+        }
+
+        Gantt {
+            algorithm: schedule.algorithm.clone(),
+            makespan: schedule.makespan(),
+            lanes,
+            colors,
+            names,
+            jobs: schedule.jobs.clone(),
+        }
+    }
+
+    /// Times `cpu` stopped one job and started another in the same tick.
+    pub fn switches(&self, cpu: usize) -> Vec<u32> {
+        self.lanes[cpu]
+            .windows(2)
+            .filter(|pair| {
+                pair[0].end == pair[1].start
+                    && pair[0].job.is_some()
+                    && pair[1].job.is_some()
+                    && pair[0].job != pair[1].job
+            })
+            .map(|pair| pair[1].start)
+            .collect()
+    }
+
+    /// Colored blocks, one column for every tick or every few ticks of a long
+    /// schedule. `.` is idle, `~` is switch overhead and `^` marks a context switch.
+    pub fn terminal(&self) -> String {
+        let scale = self.makespan.div_ceil(TERMINAL_WIDTH).max(1);
+        let columns = self.makespan.div_ceil(scale) as usize;
+        let mut chart = format!("\n[Gantt chart: {}]\n", self.algorithm);
+
+        for (cpu, lane) in self.lanes.iter().enumerate() {
+            // the job running or switched to at the first tick of every column
+            let mut cells: Vec<Option<(&str, bool)>> = vec![None; columns];
+            for segment in lane {
+                if let Some(name) = &segment.job {
+                    for tick in (segment.start..segment.end).filter(|tick| tick % scale == 0) {
+                        cells[(tick / scale) as usize] = Some((name, segment.overhead));
+                    }
+                }
+            }
+
+            write!(chart, "CPU{:<3}|", cpu).unwrap();
+
+            let mut column = 0;
+            while column < columns {
+                let run = cells[column..]
+                    .iter()
+                    .take_while(|&&cell| cell == cells[column])
+                    .count();
+
+                match cells[column] {
+                    Some((_, true)) => write!(chart, "\x1b[2m{}\x1b[0m", "~".repeat(run)).unwrap(),
+                    Some((name, false)) => {
+                        let label = name.chars().take(run).collect::<String>();
+                        write!(
+                            chart,
+                            "\x1b[30;{}m{:<width$}\x1b[0m",
+                            ANSI_COLORS[self.colors[name] % ANSI_COLORS.len()],
+                            label,
+                            width = run
+                        )
+                        .unwrap();
+                    }
+                    None => write!(chart, "\x1b[2m{}\x1b[0m", ".".repeat(run)).unwrap(),
+                }
+
+                column += run;
+            }
+
+            chart += "|\n";
+
+            let mut marks = vec![' '; columns];
+            for switch in self.switches(cpu) {
+                marks[(switch / scale) as usize] = '^';
+            }
+            writeln!(
+                chart,
+                "       {}",
+                marks.iter().collect::<String>().trim_end()
+            )
+            .unwrap();
+        }
+
+        // a label every 10 columns and at the end, unless it would touch the last one
+        let mut axis = String::new();
+        for column in (0..columns).step_by(10).chain([columns]) {
+            if column > 0 && axis.chars().count() >= column + 7 {
+                continue;
+            }
+
+            let label = (column as u32 * scale).min(self.makespan).to_string();
+            axis += &" ".repeat(column + 7 - axis.chars().count());
+            axis += &label;
+        }
+        writeln!(chart, "{}", axis).unwrap();
+
+        for name in &self.names {
+            write!(
+                chart,
+                "\x1b[30;{}m {} \x1b[0m ",
+                ANSI_COLORS[self.colors[name] % ANSI_COLORS.len()],
+                name
+            )
+            .unwrap();
+        }
+        writeln!(
+            chart,
+            "\x1b[2m.\x1b[0m idle  \x1b[2m~\x1b[0m switch overhead  ^ context switch"
+        )
+        .unwrap();
+
+        if scale > 1 {
+            writeln!(chart, "1 column = {} ticks", scale).unwrap();
+        }
+
+        chart
+    }
+
+    /// A standalone SVG document. Idle gaps are hatched and context switches are red
+    /// lines, hovering a block shows the job and its ticks.
+    pub fn svg(&self) -> String {
+        let tick_width = (SVG_WIDTH / self.makespan.max(1) as f32).min(24.0);
+        let width = LEFT + tick_width * self.makespan as f32 + 20.0;
+        let axis_y = TOP + LANE_HEIGHT * self.lanes.len() as f32 + 8.0;
+        let height = axis_y + 60.0;
+        let x = |tick: u32| LEFT + tick as f32 * tick_width;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" font-family=\"monospace\" font-size=\"12\">\n",
+            width, height
+        );
+        svg += "<defs><pattern id=\"idle\" width=\"6\" height=\"6\" patternUnits=\"userSpaceOnUse\" patternTransform=\"rotate(45)\"><rect width=\"6\" height=\"6\" fill=\"#f4f4f4\"/><line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"6\" stroke=\"#bbb\" stroke-width=\"2\"/></pattern></defs>\n";
+        writeln!(
+            svg,
+            "<text x=\"{}\" y=\"20\" font-size=\"14\">{}</text>",
+            LEFT,
+            escape(&self.algorithm)
+        )
+        .unwrap();
+
+        for (cpu, lane) in self.lanes.iter().enumerate() {
+            let y = TOP + LANE_HEIGHT * cpu as f32;
+            writeln!(
+                svg,
+                "<text x=\"8\" y=\"{}\">CPU{}</text>",
+                y + LANE_HEIGHT / 2.0 + 4.0,
+                cpu
+            )
+            .unwrap();
+
+            for segment in lane {
+                let (left, right) = (x(segment.start), x(segment.end));
+                let (fill, label) = match &segment.job {
+                    Some(..) if segment.overhead => (SWITCH_COLOR, "switch"),
+                    Some(name) => (
+                        SVG_COLORS[self.colors[name] % SVG_COLORS.len()],
+                        name.as_str(),
+                    ),
+                    None => ("url(#idle)", "idle"),
+                };
+
+                writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"#fff\"><title>{} {}-{}</title></rect>",
+                    left,
+                    y + 2.0,
+                    right - left,
+                    LANE_HEIGHT - 4.0,
+                    fill,
+                    escape(label),
+                    segment.start,
+                    segment.end
+                )
+                .unwrap();
+
+                // only when the name fits into the block
+                if segment.job.is_some()
+                    && !segment.overhead
+                    && right - left >= 8.0 * label.chars().count() as f32 + 4.0
+                {
+                    writeln!(
+                        svg,
+                        "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                        (left + right) / 2.0,
+                        y + LANE_HEIGHT / 2.0 + 4.0,
+                        escape(label)
+                    )
+                    .unwrap();
+                }
+            }
+
+            for switch in self.switches(cpu) {
+                writeln!(
+                    svg,
+                    "<line x1=\"{0}\" y1=\"{1}\" x2=\"{0}\" y2=\"{2}\" stroke=\"#d00\" stroke-width=\"2\"><title>context switch at {3}</title></line>",
+                    x(switch),
+                    y,
+                    y + LANE_HEIGHT,
+                    switch
+                )
+                .unwrap();
+            }
+        }
+
+        // the smallest of 1, 2, 5, 10, 20, 50, ... ticks that leaves 30 pixels per label
+        let mut step = 1;
+        while step as f32 * tick_width < 30.0 {
+            step = match step.to_string().chars().next() {
+                Some('2') => step / 2 * 5,
+                _ => step * 2,
+            };
+        }
+
+        writeln!(
+            svg,
+            "<line x1=\"{0}\" y1=\"{1}\" x2=\"{2}\" y2=\"{1}\" stroke=\"#333\"/>",
+            LEFT,
+            axis_y,
+            x(self.makespan)
+        )
+        .unwrap();
+        for tick in (0..=self.makespan).step_by(step as usize) {
+            writeln!(
+                svg,
+                "<line x1=\"{0}\" y1=\"{1}\" x2=\"{0}\" y2=\"{2}\" stroke=\"#333\"/><text x=\"{0}\" y=\"{3}\" text-anchor=\"middle\">{4}</text>",
+                x(tick),
+                axis_y,
+                axis_y + 4.0,
+                axis_y + 16.0,
+                tick
+            )
+            .unwrap();
+        }
+
+        let mut legend_x = LEFT;
+        let legend_y = axis_y + 32.0;
+        let legend = self
+            .names
+            .iter()
+            .map(|name| {
+                (
+                    SVG_COLORS[self.colors[name] % SVG_COLORS.len()],
+                    name.as_str(),
+                )
+            })
+            .chain([("url(#idle)", "idle")])
+            .chain(
+                self.lanes
+                    .iter()
+                    .flatten()
+                    .any(|segment| segment.overhead)
+                    .then_some((SWITCH_COLOR, "switch")),
+            );
+
+        for (fill, label) in legend {
+            writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"12\" height=\"12\" fill=\"{}\"/><text x=\"{}\" y=\"{}\">{}</text>",
+                legend_x,
+                legend_y,
+                fill,
+                legend_x + 16.0,
+                legend_y + 10.0,
+                escape(label)
+            )
+            .unwrap();
+            legend_x += 28.0 + 8.0 * label.chars().count() as f32;
+        }
+
+        writeln!(
+            svg,
+            "<line x1=\"{0}\" y1=\"{1}\" x2=\"{0}\" y2=\"{2}\" stroke=\"#d00\" stroke-width=\"2\"/><text x=\"{3}\" y=\"{4}\">context switch</text>",
+            legend_x + 6.0,
+            legend_y,
+            legend_y + 12.0,
+            legend_x + 16.0,
+            legend_y + 10.0
+        )
+        .unwrap();
+
+        svg += "</svg>\n";
+        svg
+    }
+
+    /// A self-contained HTML page with the SVG chart and the metrics of every job.
+    pub fn html(&self) -> String {
+        let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        writeln!(html, "<title>{}</title>", escape(&self.algorithm)).unwrap();
+        html += "<style>\nbody { font-family: sans-serif; margin: 2em; }\ntable { border-collapse: collapse; margin-top: 1em; }\nth, td { border: 1px solid #ccc; padding: 4px 10px; text-align: right; }\n.swatch { display: inline-block; width: 10px; height: 10px; margin-right: 6px; }\n</style>\n</head>\n<body>\n";
+        writeln!(html, "<h1>{}</h1>", escape(&self.algorithm)).unwrap();
+        html += &self.svg();
+
+        html += "<table>\n<tr><th>名称</th><th>到达时间</th><th>服务时间</th><th>完成时间</th><th>周转时间</th><th>带权周转时间</th><th>等待时间</th><th>响应时间</th></tr>\n";
+        for job in &self.jobs {
+            let color = self
+                .colors
+                .get(&job.name)
+                .map_or("#fff", |&color| SVG_COLORS[color % SVG_COLORS.len()]);
+
+            writeln!(
+                html,
+                "<tr><td><span class=\"swatch\" style=\"background: {}\"></span>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{}</td><td>{}</td></tr>",
+                color,
+                escape(&job.name),
+                job.arrival_time,
+                job.service_time,
+                job.finish_time,
+                job.whole_time,
+                job.weight_whole_time,
+                job.waiting_time,
+                job.response_time
+            )
+            .unwrap();
+        }
+
+        html += "</table>\n</body>\n</html>\n";
+        html
+    }
+}
+
+/// Escapes the characters XML gives a meaning, job names come from user files.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        build_jobs, fcfs::FcfsScheduler, gantt::Gantt, rr::RrScheduler, Job, Scheduler,
+        SchedulerConfig,
+    };
+
+    #[test]
+    fn test() {
+        let jobs: Vec<Job> = vec![
+            Job::new("A", 0, 2),
+            Job::new("B", 0, 2),
+            Job::new("C<1>", 5, 1),
+        ];
+        let schedule = FcfsScheduler::new(jobs).run(&SchedulerConfig::default());
+        let gantt = Gantt::new(&schedule);
+        print!("{}", gantt.terminal());
+
+        // B takes over from A at 2, the CPU idles from 4 to 5 before C
+        assert_eq!(gantt.switches(0), vec![2]);
+        assert!(gantt.terminal().contains("CPU0  |"));
+
+        let svg = gantt.svg();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert!(svg.contains("<title>idle 4-5</title>"));
+        assert!(svg.contains("context switch at 2"));
+        assert!(svg.contains("C&lt;1&gt;") && !svg.contains("C<1>"));
+
+        let html = gantt.html();
+        assert!(html.contains("<svg") && html.contains("<td>6</td>"));
+    }
+
+    #[test]
+    fn round_robin() {
+        let schedule = RrScheduler::new(build_jobs()).run(&SchedulerConfig::default().quantum(2));
+        let gantt = Gantt::new(&schedule);
+        print!("{}", gantt.terminal());
+
+        // every slice that ends with another job waiting is a switch
+        let blocks = schedule.segments.len() as u32;
+        assert_eq!(gantt.switches(0).len() as u32, blocks - 1);
+    }
+}
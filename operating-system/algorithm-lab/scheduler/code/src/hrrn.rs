@@ -0,0 +1,119 @@
+use std::cmp::Ordering;
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+pub struct HrrnScheduler {
+    jobs: Vec<Job>,
+}
+
+/// Response ratio = (waiting time + service time) / service time, the service time
+/// being the next CPU burst.
+fn response_ratio(waiting: u32, burst: u32) -> f32 {
+    (waiting + burst) as f32 / burst as f32
+}
+
+/// Compares the response ratios of two (waiting, burst) pairs exactly, without going
+/// through floats.
+fn compare_ratio(a: (u32, u32), b: (u32, u32)) -> Ordering {
+    let ratio_a = (a.0 + a.1) as u64 * b.1 as u64;
+    let ratio_b = (b.0 + b.1) as u64 * a.1 as u64;
+
+    ratio_a.cmp(&ratio_b)
+}
+
+/// Ready jobs in the order they became ready, scanned for the highest ratio.
+#[derive(Debug, Clone, Default)]
+struct HrrnQueue {
+    ready: Vec<usize>,
+}
+
+impl Policy for HrrnQueue {
+    fn enqueue(&mut self, job: usize, _ctx: &mut Context) {
+        self.ready.push(job);
+    }
+
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        let ratios = self
+            .ready
+            .iter()
+            .map(|&idx| (ctx.now - ctx.ready_since(idx), ctx.remaining(idx)))
+            .collect::<Vec<(u32, u32)>>();
+
+        // RATIO: log every candidate so the decision can be followed
+        for (&candidate, &(waiting, burst)) in self.ready.iter().zip(&ratios) {
+            let detail = format!(
+                "({} + {}) / {} = {:.2}",
+                waiting,
+                burst,
+                burst,
+                response_ratio(waiting, burst)
+            );
+            ctx.log("RATIO", Some(candidate), &self.ready, Some(detail));
+        }
+
+        // the earliest arrival wins ties, hence the reversed scan
+        let (pos, &(waiting, burst)) = ratios
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by(|(_, &a), (_, &b)| compare_ratio(a, b))?;
+        let selected = self.ready.remove(pos);
+
+        let detail = format!("selected with ratio {:.2}", response_ratio(waiting, burst));
+        Some(Pick::new(selected).detail(detail))
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        self.ready.clone()
+    }
+
+    fn len(&self) -> usize {
+        self.ready.len()
+    }
+}
+
+impl Scheduler for HrrnScheduler {
+    fn new(mut jobs: Vec<Job>) -> HrrnScheduler {
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        HrrnScheduler { jobs }
+    }
+
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("HRRN（高响应比优先）");
+
+        Engine::new(&mut self.jobs, config).run(&mut HrrnQueue::default(), &mut schedule);
+
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_jobs, hrrn::HrrnScheduler, Job, Scheduler, SchedulerConfig};
+
+    #[test]
+    fn test() {
+        let jobs: Vec<Job> = build_jobs();
+
+        let mut scheduler = HrrnScheduler::new(jobs);
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+
+        let order = schedule
+            .events
+            .iter()
+            .filter(|process| process.status == "FINISHED")
+            .map(|process| {
+                let job = process.job.as_ref().unwrap();
+                (job.name.as_str(), job.finish_time.unwrap())
+            })
+            .collect::<Vec<(&str, u32)>>();
+
+        // B and E tie at ratio 2.00 at time 8, B arrived first
+        assert_eq!(order, vec![("A", 5), ("C", 8), ("B", 15), ("E", 17), ("D", 25)]);
+    }
+}
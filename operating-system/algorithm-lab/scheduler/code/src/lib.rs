@@ -1,6 +1,105 @@
+pub mod burst;
+pub mod cfs;
+pub mod compare;
+pub mod dag;
+pub mod engine;
+pub mod fair;
 pub mod fcfs;
+pub mod gantt;
+pub mod hrrn;
+pub mod loader;
+pub mod lottery;
+pub mod metrics;
+pub mod mlfq;
+pub mod mlq;
+pub mod priority;
+pub mod realtime;
+pub mod report;
 pub mod rr;
+pub mod share;
 pub mod sjf;
+pub mod smp;
+pub mod srtf;
+pub mod stride;
+pub mod validate;
+pub mod workload;
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use metrics::Metrics;
+use serde::Serialize;
+
+/// Fixed class of a job, used by the multilevel queue scheduler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JobClass {
+    System,
+    Interactive,
+    #[default]
+    Batch,
+}
+
+impl JobClass {
+    pub const ALL: [JobClass; 3] = [JobClass::System, JobClass::Interactive, JobClass::Batch];
+
+    /// Position of the class in `ALL`, the highest first.
+    pub fn index(&self) -> usize {
+        match self {
+            JobClass::System => 0,
+            JobClass::Interactive => 1,
+            JobClass::Batch => 2,
+        }
+    }
+}
+
+impl fmt::Display for JobClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JobClass::System => write!(f, "system"),
+            JobClass::Interactive => write!(f, "interactive"),
+            JobClass::Batch => write!(f, "batch"),
+        }
+    }
+}
+
+impl FromStr for JobClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<JobClass, String> {
+        match s.trim().to_lowercase().as_str() {
+            "system" => Ok(JobClass::System),
+            "interactive" => Ok(JobClass::Interactive),
+            "batch" => Ok(JobClass::Batch),
+            other => Err(format!("unknown job class: {}", other)),
+        }
+    }
+}
+
+/// One phase of a job, it alternates between the CPU and the I/O device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Burst {
+    Cpu(u32),
+    Io(u32),
+}
+
+impl Burst {
+    /// Reads `[cpu, io, cpu, ...]`, e.g. the bursts column of a job file.
+    pub fn alternating(ticks: &[u32]) -> Vec<Burst> {
+        ticks
+            .iter()
+            .enumerate()
+            .map(|(idx, &ticks)| match idx % 2 {
+                0 => Burst::Cpu(ticks),
+                _ => Burst::Io(ticks),
+            })
+            .collect()
+    }
+
+    pub fn ticks(&self) -> u32 {
+        match self {
+            Burst::Cpu(ticks) | Burst::Io(ticks) => *ticks,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Job {
@@ -11,166 +110,623 @@ pub struct Job {
     pub whole_time: Option<u32>,
     pub weight_whole_time: Option<f32>,
     pub served_time: u32,
+    /// Longest the job waited ready for a CPU at a time.
+    pub max_wait: u32,
+    /// Smaller value means higher priority, 0 is the highest.
+    pub priority: u32,
+    pub class: JobClass,
+    /// User or group the job belongs to, the fair-share scheduler divides the CPU
+    /// between them.
+    pub owner: Option<String>,
+    /// Share of the CPU for the proportional-share schedulers.
+    pub tickets: u32,
+    /// Niceness from -20 to 19 for the CFS scheduler, lower means a bigger weight.
+    pub nice: i32,
+    /// Absolute deadline, only used by the real-time schedulers.
+    pub deadline: Option<u32>,
+    /// CPUs the job may run on in multiprocessor mode, empty means any.
+    pub affinity: Vec<usize>,
+    /// CPU and I/O bursts, empty means a single CPU burst of `service_time`. Every
+    /// policy on the engine blocks the job on its I/O bursts.
+    pub bursts: Vec<Burst>,
+    /// Names of the jobs that have to finish before this one is ready, the engine
+    /// holds it back until then.
+    pub predecessors: Vec<String>,
+}
+
+impl Job {
+    pub fn new(name: &str, arrival_time: u32, service_time: u32) -> Job {
+        Job {
+            name: name.to_string(),
+            arrival_time,
+            service_time,
+            finish_time: None,
+            whole_time: None,
+            weight_whole_time: None,
+            served_time: 0,
+            max_wait: 0,
+            priority: 0,
+            class: JobClass::default(),
+            owner: None,
+            tickets: 100,
+            nice: 0,
+            deadline: None,
+            affinity: Vec::new(),
+            bursts: Vec::new(),
+            predecessors: Vec::new(),
+        }
+    }
+
+    /// A job alternating between CPU and I/O bursts, starting and ending on the CPU.
+    /// `service_time` is the sum of the CPU bursts.
+    pub fn with_bursts(name: &str, arrival_time: u32, bursts: Vec<Burst>) -> Job {
+        assert!(
+            matches!(bursts.first(), Some(Burst::Cpu(..)))
+                && matches!(bursts.last(), Some(Burst::Cpu(..))),
+            "{}: bursts must start and end on the CPU",
+            name
+        );
+        assert!(
+            bursts.windows(2).all(|pair| matches!(
+                pair,
+                [Burst::Cpu(..), Burst::Io(..)] | [Burst::Io(..), Burst::Cpu(..)]
+            )),
+            "{}: CPU and I/O bursts must alternate",
+            name
+        );
+        assert!(
+            bursts.iter().all(|burst| burst.ticks() > 0),
+            "{}: empty burst",
+            name
+        );
+
+        let service_time = bursts
+            .iter()
+            .filter(|burst| matches!(burst, Burst::Cpu(..)))
+            .map(Burst::ticks)
+            .sum();
+
+        Job {
+            bursts,
+            ..Job::new(name, arrival_time, service_time)
+        }
+    }
+
+    pub fn has_io(&self) -> bool {
+        self.bursts.len() > 1
+    }
+
+    pub fn can_run_on(&self, cpu: usize) -> bool {
+        self.affinity.is_empty() || self.affinity.contains(&cpu)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SchedulerProcess {
-    job: Option<Job>,
-    status: String,
-    current_time: u32,
-    current_queue: Vec<Job>,
+    pub job: Option<Job>,
+    pub status: String,
+    pub current_time: u32,
+    pub current_queue: Vec<Job>,
+    /// Extra information about the decision, e.g. the computed ratios.
+    pub detail: Option<String>,
+    /// CPU the event happened on, always 0 for the single processor schedulers.
+    pub cpu: usize,
 }
 
-pub struct SchedulerResult {
-    pub task_num: f32,
-    pub scheduled: Vec<SchedulerProcess>,
-    pub serviced_time: f32,
-    pub weight_serviced_time: f32,
+/// Outcome of one finished job.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobMetrics {
+    pub name: String,
+    pub arrival_time: u32,
+    pub service_time: u32,
+    pub finish_time: u32,
+    /// Finish time minus arrival time.
+    pub whole_time: u32,
+    /// Turnaround divided by the service time.
+    pub weight_whole_time: f32,
+    /// Turnaround minus the CPU and I/O bursts, the time spent in a queue.
+    pub waiting_time: u32,
+    /// First dispatch minus arrival time.
+    pub response_time: u32,
+    /// Longest the job waited ready for a CPU at a time.
+    pub max_wait: u32,
+    /// Whether `max_wait` went past the starvation threshold of the run.
+    pub starved: bool,
 }
 
-impl SchedulerResult {
-    pub fn new() -> SchedulerResult {
-        SchedulerResult {
-            task_num: 0.0,
-            scheduled: Vec::new(),
-            serviced_time: 0.0,
-            weight_serviced_time: 0.0,
+/// Ticks `start..end` a job ran on `cpu` without interruption, `job` is `None` for
+/// a gap the CPU was idle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Segment {
+    pub job: Option<String>,
+    pub cpu: usize,
+    pub start: u32,
+    pub end: u32,
+    /// The CPU spent the ticks switching to `job` instead of running it.
+    pub overhead: bool,
+}
+
+impl Segment {
+    pub fn ticks(&self) -> u32 {
+        self.end - self.start
+    }
+}
+
+/// A waiting job moves up one priority level every `interval` ticks, by at most
+/// `cap` levels. For SJF a level is one tick of its burst. The levels are lost once
+/// the job runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aging {
+    pub interval: u32,
+    pub cap: u32,
+}
+
+impl Aging {
+    pub fn new(interval: u32, cap: u32) -> Aging {
+        assert!(interval > 0, "aging interval must be positive");
+
+        Aging { interval, cap }
+    }
+
+    /// `key` after waiting `waited` ticks, 0 is the best it gets.
+    pub fn aged(&self, key: u32, waited: u32) -> u32 {
+        key.saturating_sub((waited / self.interval).min(self.cap))
+    }
+
+    /// Ticks of waiting until `key` ages below `target`, `None` if it never does.
+    pub fn waiting_to_beat(&self, key: u32, target: u32) -> Option<u32> {
+        if key < target {
+            return Some(0);
+        }
+
+        let levels = key - target + 1;
+        match target > 0 && levels <= self.cap {
+            true => levels.checked_mul(self.interval),
+            false => None,
         }
     }
+}
+
+impl fmt::Display for Aging {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "aging: one level every {} ticks, at most {} levels",
+            self.interval, self.cap
+        )
+    }
+}
+
+/// Settings shared by every policy, a policy ignores what it has no use for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchedulerConfig {
+    /// Ticks a job runs before it is preempted, used by RR, lottery and stride.
+    pub quantum: u32,
+    /// Seed of the randomized policies.
+    pub seed: u64,
+    /// Whether the schedule keeps the event log, off for huge workloads where only
+    /// the segments and the metrics matter.
+    pub event_log: bool,
+    /// Ticks a CPU spends switching to another job before it runs.
+    pub context_switch: u32,
+    /// Ticks a CPU spends dispatching the job it ran last, `context_switch` if `None`.
+    pub same_job_switch: Option<u32>,
+    /// Raises the priority of waiting jobs, used by SJF and the priority policies.
+    pub aging: Option<Aging>,
+    /// A job that waited longer than this at a time is marked as starved.
+    pub starvation: Option<u32>,
+}
 
-    pub fn len(&mut self) -> usize {
-        self.scheduled.len()
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig {
+            quantum: 1,
+            seed: 0,
+            event_log: true,
+            context_switch: 0,
+            same_job_switch: None,
+            aging: None,
+            starvation: None,
+        }
     }
+}
 
+impl SchedulerConfig {
+    pub fn quantum(mut self, quantum: u32) -> SchedulerConfig {
+        assert!(quantum > 0, "quantum must be positive");
+
+        self.quantum = quantum;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> SchedulerConfig {
+        self.seed = seed;
+        self
+    }
+
+    pub fn event_log(mut self, event_log: bool) -> SchedulerConfig {
+        self.event_log = event_log;
+        self
+    }
+
+    pub fn context_switch(mut self, cost: u32) -> SchedulerConfig {
+        self.context_switch = cost;
+        self
+    }
+
+    pub fn same_job_switch(mut self, cost: u32) -> SchedulerConfig {
+        self.same_job_switch = Some(cost);
+        self
+    }
+
+    pub fn aging(mut self, aging: Aging) -> SchedulerConfig {
+        self.aging = Some(aging);
+        self
+    }
+
+    pub fn starvation(mut self, threshold: u32) -> SchedulerConfig {
+        self.starvation = Some(threshold);
+        self
+    }
+
+    /// Ticks of a dispatch, `same` when the CPU ran the job right before.
+    pub fn switch_cost(&self, same: bool) -> u32 {
+        match same {
+            true => self.same_job_switch.unwrap_or(self.context_switch),
+            false => self.context_switch,
+        }
+    }
+}
+
+/// Everything a run produced: the event log in time order, the CPU time every job
+/// got, the metrics of every finished job in the order it finished and the policy
+/// specific notes.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    /// Name of the policy, e.g. `FCFS（先来先服务）`.
+    pub algorithm: String,
+    pub events: Vec<SchedulerProcess>,
+    /// Busy periods of the CPUs, merged while a job keeps running.
+    pub segments: Vec<Segment>,
+    pub jobs: Vec<JobMetrics>,
+    /// Printed after the tables, e.g. the parameters or the share of every job.
+    pub notes: Vec<String>,
+    /// Jobs that waited longer than this at a time are marked as starved.
+    pub starvation: Option<u32>,
+    /// First dispatch of every job that ran, for the response time.
+    dispatched: HashMap<String, u32>,
+}
+
+impl Schedule {
+    pub fn new(algorithm: &str) -> Schedule {
+        Schedule {
+            algorithm: algorithm.to_string(),
+            events: Vec::new(),
+            segments: Vec::new(),
+            jobs: Vec::new(),
+            notes: Vec::new(),
+            starvation: None,
+            dispatched: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Appends an event, a job with a finish time has finished. Its CPU time has to
+    /// be given to `ran` first.
     pub fn log(&mut self, process: SchedulerProcess) {
-        if process.job.is_some() {
-            let job = process.job.as_ref().unwrap();
-            if job.finish_time.is_some() {
-                self.task_num += 1.0;
-                self.serviced_time += job.whole_time.unwrap() as f32;
-                self.weight_serviced_time += job.weight_whole_time.unwrap();
+        if let Some(job) = &process.job {
+            self.finish(job);
+        }
+
+        self.events.push(process);
+    }
+
+    /// Records the metrics of `job` if it has finished, what `log` does without an
+    /// event.
+    pub fn finish(&mut self, job: &Job) {
+        if let Some(finish_time) = job.finish_time {
+            let whole_time = job.whole_time.unwrap();
+            let io_time = job
+                .bursts
+                .iter()
+                .filter(|burst| matches!(burst, Burst::Io(..)))
+                .map(Burst::ticks)
+                .sum::<u32>();
+            let dispatched = self.dispatched.get(&job.name).copied();
+
+            self.jobs.push(JobMetrics {
+                name: job.name.clone(),
+                arrival_time: job.arrival_time,
+                service_time: job.service_time,
+                finish_time,
+                whole_time,
+                weight_whole_time: job.weight_whole_time.unwrap(),
+                waiting_time: whole_time - job.service_time - io_time,
+                response_time: dispatched.unwrap_or(finish_time) - job.arrival_time,
+                max_wait: job.max_wait,
+                starved: self
+                    .starvation
+                    .is_some_and(|threshold| job.max_wait > threshold),
+            });
+        }
+    }
+
+    /// Records that `job` ran on `cpu` for `ticks` from `start`, continuing the last
+    /// segment of the CPU if it ran there right before.
+    pub fn ran(&mut self, job: &str, cpu: usize, start: u32, ticks: u32) {
+        if ticks == 0 {
+            return;
+        }
+
+        match self.dispatched.get_mut(job) {
+            Some(dispatched) => *dispatched = (*dispatched).min(start),
+            None => {
+                self.dispatched.insert(job.to_string(), start);
+            }
+        }
+
+        let last = self
+            .segments
+            .iter_mut()
+            .rev()
+            .find(|segment| segment.cpu == cpu);
+        if let Some(last) = last {
+            if last.end == start && last.job.as_deref() == Some(job) && !last.overhead {
+                last.end += ticks;
+                return;
+            }
+        }
+
+        self.segments.push(Segment {
+            job: Some(job.to_string()),
+            cpu,
+            start,
+            end: start + ticks,
+            overhead: false,
+        });
+    }
+
+    /// Records that `cpu` spent `ticks` from `start` switching to `job`, continuing
+    /// the switch it was in.
+    pub fn switched(&mut self, job: &str, cpu: usize, start: u32, ticks: u32) {
+        if ticks == 0 {
+            return;
+        }
+
+        let last = self
+            .segments
+            .iter_mut()
+            .rev()
+            .find(|segment| segment.cpu == cpu);
+        if let Some(last) = last {
+            if last.end == start && last.job.as_deref() == Some(job) && last.overhead {
+                last.end += ticks;
+                return;
             }
         }
 
-        self.scheduled.push(process);
+        self.segments.push(Segment {
+            job: Some(job.to_string()),
+            cpu,
+            start,
+            end: start + ticks,
+            overhead: true,
+        });
+    }
+
+    /// Number of CPUs the events or segments mention.
+    pub fn cpus(&self) -> usize {
+        let events = self.events.iter().map(|process| process.cpu);
+        let segments = self.segments.iter().map(|segment| segment.cpu);
+
+        events.chain(segments).max().map_or(1, |cpu| cpu + 1)
+    }
+
+    /// Ticks from 0 until the last job finished or the last segment ended.
+    pub fn makespan(&self) -> u32 {
+        let finished = self.jobs.iter().map(|job| job.finish_time);
+        let segments = self.segments.iter().map(|segment| segment.end);
+
+        finished.chain(segments).max().unwrap_or(0)
+    }
+
+    /// Periods before the makespan every CPU spent without a job, in time order.
+    pub fn idle_gaps(&self) -> Vec<Segment> {
+        let makespan = self.makespan();
+        let mut gaps = Vec::new();
+
+        for cpu in 0..self.cpus() {
+            let mut busy = self
+                .segments
+                .iter()
+                .filter(|segment| segment.cpu == cpu)
+                .map(|segment| (segment.start, segment.end))
+                .collect::<Vec<(u32, u32)>>();
+            busy.sort();
+            busy.push((makespan, makespan));
+
+            let mut time = 0;
+            for (start, end) in busy {
+                if start > time {
+                    gaps.push(Segment {
+                        job: None,
+                        cpu,
+                        start: time,
+                        end: start,
+                        overhead: false,
+                    });
+                }
+                time = time.max(end);
+            }
+        }
+
+        gaps.sort_by_key(|gap| (gap.start, gap.cpu));
+        gaps
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        Metrics::new(self)
+    }
+
+    pub fn note(&mut self, note: String) {
+        self.notes.push(note);
+    }
+
+    /// Metrics of the job called `name`.
+    pub fn job(&self, name: &str) -> Option<&JobMetrics> {
+        self.jobs.iter().find(|job| job.name == name)
+    }
+
+    pub fn finish_time(&self, name: &str) -> Option<u32> {
+        self.job(name).map(|job| job.finish_time)
+    }
+
+    pub fn average_whole_time(&self) -> f32 {
+        self.jobs.iter().map(|job| job.whole_time).sum::<u32>() as f32 / self.jobs.len() as f32
     }
 
-    pub fn print(&mut self) {
+    pub fn average_weight_whole_time(&self) -> f32 {
+        self.jobs
+            .iter()
+            .map(|job| job.weight_whole_time)
+            .sum::<f32>()
+            / self.jobs.len() as f32
+    }
+
+    pub fn print(&self) {
+        print!("{}", self);
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "\n\n[Scheduler algorithm: {}]", self.algorithm)?;
+
+        let multiprocessor = self.events.iter().any(|process| process.cpu > 0);
+
         for SchedulerProcess {
             job,
             status,
             current_time,
             current_queue,
-        } in &self.scheduled
+            detail,
+            cpu,
+        } in &self.events
         {
-            print!("| {0}th \t|", current_time);
-            print!(" {:<10} | ", status);
+            write!(f, "| {0}th \t|", current_time)?;
+
+            if multiprocessor {
+                write!(f, " CPU{} |", cpu)?;
+            }
+
+            write!(f, " {:<10} | ", status)?;
 
             match job {
-                Some(job) => print!("{:<10}", job.name),
-                None => print!("{:<10}", ""),
+                Some(job) => write!(f, "{:<10}", job.name)?,
+                None => write!(f, "{:<10}", "")?,
             }
 
-            if current_queue.len() > 0 {
-                print!(" | QUEUE: ");
+            if !current_queue.is_empty() {
+                write!(f, " | QUEUE: ")?;
 
                 for job in current_queue {
-                    print!("{} ", job.name);
+                    write!(f, "{} ", job.name)?;
                 }
             }
 
-            print!("\n");
+            if let Some(detail) = detail {
+                write!(f, " | {}", detail)?;
+            }
+
+            writeln!(f)?;
         }
 
-        println!(
-            "\n| {0: <8} | {1: <6} | {2: <6} | {3: <6} | {4: <6} | {5: <6} |",
-            "名称", "到达时间", "服务时间", "完成时间", "周转时间", "带权周转时间"
-        );
+        writeln!(
+            f,
+            "\n| {0: <8} | {1: <6} | {2: <6} | {3: <6} | {4: <6} | {5: <6} | {6: <6} | {7: <6} | {8: <6} |",
+            "名称",
+            "到达时间",
+            "服务时间",
+            "完成时间",
+            "周转时间",
+            "带权周转时间",
+            "等待时间",
+            "响应时间",
+            "最长等待"
+        )?;
 
-        for SchedulerProcess { job, .. } in &self.scheduled {
-            match job {
-                Some(job) => {
-                    if job.finish_time.is_none() {
-                        continue;
-                    }
-                    println!(
-                        "| {0: <10} | {1: <10} | {2: <10} | {3: <10} | {4: <10} | {5: <10} |",
-                        job.name,
-                        job.arrival_time,
-                        job.service_time,
-                        job.finish_time.unwrap(),
-                        job.whole_time.unwrap(),
-                        job.weight_whole_time.unwrap()
-                    )
+        for job in &self.jobs {
+            writeln!(
+                f,
+                "| {0: <10} | {1: <10} | {2: <10} | {3: <10} | {4: <10} | {5: <10} | {6: <10} | {7: <10} | {8: <10} |",
+                job.name,
+                job.arrival_time,
+                job.service_time,
+                job.finish_time,
+                job.whole_time,
+                job.weight_whole_time,
+                job.waiting_time,
+                job.response_time,
+                match job.starved {
+                    true => format!("{}!", job.max_wait),
+                    false => job.max_wait.to_string(),
                 }
-                None => {}
-            }
+            )?;
         }
 
-        let average_wt = self.serviced_time / self.task_num;
-        let average_wwt = self.weight_serviced_time / self.task_num;
+        if let Some(threshold) = self.starvation {
+            let starved = self.jobs.iter().filter(|job| job.starved).count();
+            writeln!(f, "饥饿阈值: {}, 饥饿作业: {} (! 标记)", threshold, starved)?;
+        }
 
-        println!(
+        writeln!(
+            f,
             "平均周转时间: {}, 平均带权周转时间: {}",
-            average_wt, average_wwt
-        );
+            self.average_whole_time(),
+            self.average_weight_whole_time()
+        )?;
+        write!(f, "{}", self.metrics())?;
+
+        for note in &self.notes {
+            writeln!(f, "{}", note)?;
+        }
+
+        Ok(())
     }
 }
 
 pub trait Scheduler {
-    fn new(jobs: Vec<Job>) -> Self;
-    fn run(&mut self);
-    fn print(&mut self);
+    fn new(jobs: Vec<Job>) -> Self
+    where
+        Self: Sized;
+
+    /// `new` for jobs whose predecessors were not checked yet, they must form a DAG.
+    fn try_new(jobs: Vec<Job>) -> Result<Self, dag::DagError>
+    where
+        Self: Sized,
+    {
+        dag::Dag::new(&jobs)?;
+        Ok(Self::new(jobs))
+    }
+
+    /// Runs the jobs given to `new` once.
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule;
 }
 
 #[cfg(test)]
 fn build_jobs() -> Vec<Job> {
     vec![
-        Job {
-            name: "A".to_string(),
-            arrival_time: 0,
-            service_time: 5,
-            finish_time: None,
-            whole_time: None,
-            weight_whole_time: None,
-            served_time: 0,
-        },
-        Job {
-            name: "B".to_string(),
-            arrival_time: 1,
-            service_time: 7,
-            finish_time: None,
-            whole_time: None,
-            weight_whole_time: None,
-            served_time: 0,
-        },
-        Job {
-            name: "C".to_string(),
-            arrival_time: 3,
-            service_time: 3,
-            finish_time: None,
-            whole_time: None,
-            weight_whole_time: None,
-            served_time: 0,
-        },
-        Job {
-            name: "D".to_string(),
-            arrival_time: 4,
-            service_time: 8,
-            finish_time: None,
-            whole_time: None,
-            weight_whole_time: None,
-            served_time: 0,
-        },
-        Job {
-            name: "E".to_string(),
-            arrival_time: 6,
-            service_time: 2,
-            finish_time: None,
-            whole_time: None,
-            weight_whole_time: None,
-            served_time: 0,
-        },
+        Job { priority: 3, ..Job::new("A", 0, 5) },
+        Job { priority: 2, ..Job::new("B", 1, 7) },
+        Job { priority: 1, ..Job::new("C", 3, 3) },
+        Job { priority: 4, ..Job::new("D", 4, 8) },
+        Job { priority: 2, ..Job::new("E", 6, 2) },
     ]
-    .clone()
 }
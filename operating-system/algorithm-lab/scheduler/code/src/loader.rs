@@ -0,0 +1,925 @@
+use std::{collections::HashSet, fmt, fs, ops::Range, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    dag::{Dag, DagError},
+    realtime::{checked_hyperperiod, PeriodicTask},
+    stride::STRIDE1,
+    Burst, Job, JobClass,
+};
+
+/// What went wrong while loading a job file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The file could not be read or has an unknown extension.
+    Io(String),
+    /// Malformed CSV, JSON or TOML.
+    Syntax(String),
+    MissingField(&'static str),
+    InvalidValue {
+        field: &'static str,
+        value: String,
+        reason: String,
+    },
+    /// A CSV row has more columns than there are fields.
+    ExtraColumn(String),
+    DuplicateName(String),
+    ZeroServiceTime(String),
+    /// The predecessors name a job that is not in the file or form a cycle.
+    Dependency(String),
+    /// The jobs up to this one, run one after another from the latest arrival, would
+    /// finish after tick `u32::MAX`.
+    Horizon(String),
+    /// A periodic task has impossible timing, or the periods have no `u32` hyperperiod
+    /// or release too many instances in it.
+    Task(String),
+}
+
+/// An error at `line` and `column` of the file, both counted from 1. Errors about the
+/// whole file are at line 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ErrorKind,
+}
+
+impl LoadError {
+    fn new(line: usize, column: usize, kind: ErrorKind) -> LoadError {
+        LoadError { line, column, kind }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}, column {}: ", self.line, self.column)?;
+        }
+
+        match &self.kind {
+            ErrorKind::Io(message) | ErrorKind::Syntax(message) => write!(f, "{}", message),
+            ErrorKind::MissingField(field) => write!(f, "missing {}", field),
+            ErrorKind::InvalidValue {
+                field,
+                value,
+                reason,
+            } => write!(f, "invalid {} {:?}: {}", field, value, reason),
+            ErrorKind::ExtraColumn(value) => write!(f, "unexpected column {:?}", value),
+            ErrorKind::DuplicateName(name) => write!(f, "duplicate job name {}", name),
+            ErrorKind::ZeroServiceTime(name) => write!(f, "{} has no service time", name),
+            ErrorKind::Horizon(name) => write!(f, "{} may finish after tick {}", name, u32::MAX),
+            ErrorKind::Dependency(message) | ErrorKind::Task(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+    Toml,
+}
+
+impl Format {
+    /// Picks the format by the extension, anything but `.json` and `.toml` is CSV.
+    pub fn of(path: &Path) -> Format {
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("json") => Format::Json,
+            Some("toml") => Format::Toml,
+            _ => Format::Csv,
+        }
+    }
+}
+
+/// Fields of one job as written in a file, before they are checked.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Record {
+    name: String,
+    #[serde(alias = "arrival")]
+    arrival_time: u32,
+    #[serde(alias = "service", default)]
+    service_time: Option<u32>,
+    #[serde(default)]
+    priority: Option<u32>,
+    #[serde(default)]
+    class: Option<String>,
+    #[serde(alias = "group", default)]
+    owner: Option<String>,
+    #[serde(default)]
+    tickets: Option<u32>,
+    #[serde(default)]
+    nice: Option<i32>,
+    #[serde(default)]
+    affinity: Option<Vec<usize>>,
+    #[serde(default)]
+    bursts: Option<Vec<u32>>,
+    #[serde(default)]
+    deadline: Option<u32>,
+    #[serde(default)]
+    predecessors: Option<Vec<String>>,
+}
+
+/// The column every field was read from, the start of the record outside of CSV.
+type Columns = [usize; FIELDS.len()];
+
+/// CSV columns in the order a file without a header has them.
+const FIELDS: [&str; 12] = [
+    "name",
+    "arrival_time",
+    "service_time",
+    "priority",
+    "class",
+    "tickets",
+    "nice",
+    "affinity",
+    "bursts",
+    "deadline",
+    "predecessors",
+    "owner",
+];
+
+fn column_of(columns: &Columns, field: &str) -> usize {
+    columns[FIELDS.iter().position(|x| *x == field).unwrap()]
+}
+
+impl Record {
+    /// Checks the fields and builds the job, `line` and `columns` locate the errors.
+    fn into_job(self, line: usize, columns: &Columns) -> Result<Job, LoadError> {
+        let invalid = |field: &'static str, value: String, reason: &str| {
+            LoadError::new(
+                line,
+                column_of(columns, field),
+                ErrorKind::InvalidValue {
+                    field,
+                    value,
+                    reason: reason.to_string(),
+                },
+            )
+        };
+
+        if self.name.trim().is_empty() {
+            return Err(LoadError::new(
+                line,
+                column_of(columns, "name"),
+                ErrorKind::MissingField("name"),
+            ));
+        }
+
+        let mut job = match (&self.bursts, self.service_time) {
+            (Some(bursts), _) => {
+                if bursts.len() % 2 == 0 || bursts.contains(&0) {
+                    return Err(invalid(
+                        "bursts",
+                        format!("{:?}", bursts),
+                        "needs non-zero CPU and I/O bursts, starting and ending on the CPU",
+                    ));
+                }
+                if bursts.iter().map(|&ticks| ticks as u64).sum::<u64>() > u32::MAX as u64 {
+                    return Err(invalid(
+                        "bursts",
+                        format!("{:?}", bursts),
+                        &format!("add up to more than {} ticks", u32::MAX),
+                    ));
+                }
+
+                let job =
+                    Job::with_bursts(&self.name, self.arrival_time, Burst::alternating(bursts));
+
+                // the service time is optional next to the bursts, but has to match them
+                if let Some(service_time) = self.service_time {
+                    if service_time != job.service_time {
+                        return Err(invalid(
+                            "service_time",
+                            service_time.to_string(),
+                            &format!("the CPU bursts add up to {}", job.service_time),
+                        ));
+                    }
+                }
+
+                job
+            }
+            (None, Some(service_time)) => Job::new(&self.name, self.arrival_time, service_time),
+            (None, None) => {
+                return Err(LoadError::new(
+                    line,
+                    column_of(columns, "service_time"),
+                    ErrorKind::MissingField("service_time"),
+                ))
+            }
+        };
+
+        if job.service_time == 0 {
+            return Err(LoadError::new(
+                line,
+                column_of(columns, "service_time"),
+                ErrorKind::ZeroServiceTime(job.name),
+            ));
+        }
+
+        if let Some(priority) = self.priority {
+            job.priority = priority;
+        }
+
+        if let Some(class) = self.class {
+            job.class = class
+                .parse::<JobClass>()
+                .map_err(|reason| invalid("class", class.clone(), &reason))?;
+        }
+
+        if let Some(tickets) = self.tickets {
+            if tickets == 0 {
+                return Err(invalid(
+                    "tickets",
+                    tickets.to_string(),
+                    "needs at least one",
+                ));
+            }
+            if tickets as u64 > STRIDE1 {
+                return Err(invalid(
+                    "tickets",
+                    tickets.to_string(),
+                    &format!("must be at most {}", STRIDE1),
+                ));
+            }
+            job.tickets = tickets;
+        }
+
+        if let Some(nice) = self.nice {
+            if !(-20..=19).contains(&nice) {
+                return Err(invalid("nice", nice.to_string(), "must be in -20..=19"));
+            }
+            job.nice = nice;
+        }
+
+        job.affinity = self.affinity.unwrap_or_default();
+        job.deadline = self.deadline;
+        job.predecessors = self.predecessors.unwrap_or_default();
+        job.owner = self.owner.filter(|owner| !owner.trim().is_empty());
+
+        Ok(job)
+    }
+}
+
+/// Reads the jobs of the file at `path` in the format its extension names.
+pub fn load(path: &Path) -> Result<Vec<Job>, LoadError> {
+    let text = fs::read_to_string(path)
+        .map_err(|why| LoadError::new(0, 0, ErrorKind::Io(format!("couldn't open it: {}", why))))?;
+
+    parse(&text, Format::of(path))
+}
+
+/// Reads jobs from `text`, every name has to be unique, the predecessors must form a
+/// DAG and the jobs have to fit before tick `u32::MAX` even run one after another.
+pub fn parse(text: &str, format: Format) -> Result<Vec<Job>, LoadError> {
+    let jobs = match format {
+        Format::Csv => parse_csv(text)?,
+        Format::Json => parse_json(text)?,
+        Format::Toml => parse_toml(text)?,
+    };
+
+    let mut names = HashSet::new();
+    for (job, line, column) in &jobs {
+        if !names.insert(job.name.as_str()) {
+            return Err(LoadError::new(
+                *line,
+                *column,
+                ErrorKind::DuplicateName(job.name.clone()),
+            ));
+        }
+    }
+
+    // the engine counts time in u32, every job finishes by the latest arrival plus
+    // the CPU and I/O ticks of all the jobs
+    let mut latest = 0;
+    let mut ticks = 0;
+    for (job, line, column) in &jobs {
+        latest = latest.max(job.arrival_time as u64);
+        ticks += match job.bursts.is_empty() {
+            true => job.service_time as u64,
+            false => job.bursts.iter().map(|burst| burst.ticks() as u64).sum(),
+        };
+
+        if latest + ticks > u32::MAX as u64 {
+            return Err(LoadError::new(
+                *line,
+                *column,
+                ErrorKind::Horizon(job.name.clone()),
+            ));
+        }
+    }
+
+    let (jobs, located): (Vec<Job>, Vec<(usize, usize)>) = jobs
+        .into_iter()
+        .map(|(job, line, column)| (job, (line, column)))
+        .unzip();
+
+    if let Err(why) = Dag::new(&jobs) {
+        // at the job that waits for an unknown one or the first job of the cycle
+        let name = match &why {
+            DagError::UnknownPredecessor { job, .. } => job,
+            DagError::Cycle(jobs) => &jobs[0],
+        };
+        let (line, column) = located[jobs.iter().position(|job| &job.name == name).unwrap()];
+
+        return Err(LoadError::new(
+            line,
+            column,
+            ErrorKind::Dependency(why.to_string()),
+        ));
+    }
+
+    Ok(jobs)
+}
+
+/// Line and column of the byte `offset` of `text`.
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+
+    (line, column)
+}
+
+/// The fields of a CSV row and the column every one starts at.
+fn split_row(row: &str) -> Vec<(&str, usize)> {
+    let mut fields = Vec::new();
+    let mut column = 1;
+    for field in row.split(',') {
+        fields.push((field.trim(), column));
+        column += field.chars().count() + 1;
+    }
+
+    fields
+}
+
+/// `name,arrival_time,service_time[,priority,class,tickets,nice,affinity,bursts,deadline,predecessors,owner]`
+/// rows. Blank lines and lines starting with `#` are skipped, empty fields keep the
+/// default, so a trailing comma is fine. A first row of field names is a header, its
+/// order is used for the rows and columns it doesn't know are ignored. The affinity,
+/// the bursts and the predecessors are separated by spaces, e.g. `0 2`, `2 3 1` and
+/// `A B`.
+fn parse_csv(text: &str) -> Result<Vec<(Job, usize, usize)>, LoadError> {
+    let mut header: Option<Vec<Option<usize>>> = None;
+    let mut jobs = Vec::new();
+    let mut first_row = true;
+
+    for (idx, row) in text.lines().enumerate() {
+        let line = idx + 1;
+
+        if row.trim().is_empty() || row.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let fields = split_row(row);
+
+        if first_row {
+            first_row = false;
+
+            let is_header = fields
+                .iter()
+                .any(|(field, _)| field.eq_ignore_ascii_case("name"))
+                && fields
+                    .iter()
+                    .all(|(field, _)| field.parse::<i64>().is_err());
+
+            if is_header {
+                header = Some(
+                    fields
+                        .iter()
+                        .map(|(field, _)| {
+                            let field = field.to_lowercase();
+                            let field = match field.as_str() {
+                                "arrival" => "arrival_time",
+                                "service" => "service_time",
+                                "group" => "owner",
+                                field => field,
+                            };
+                            FIELDS.iter().position(|x| *x == field)
+                        })
+                        .collect(),
+                );
+                continue;
+            }
+        }
+
+        let mut values: [Option<&str>; FIELDS.len()] = [None; FIELDS.len()];
+        let mut columns: Columns = [1; FIELDS.len()];
+
+        for (position, (field, column)) in fields.iter().enumerate() {
+            let target = match &header {
+                Some(header) => header.get(position).copied().flatten(),
+                None => Some(position).filter(|&x| x < FIELDS.len()),
+            };
+
+            match target {
+                Some(target) => {
+                    columns[target] = *column;
+                    if !field.is_empty() {
+                        values[target] = Some(field);
+                    }
+                }
+                // a trailing comma, or a column the header does not know
+                None if field.is_empty() || header.is_some() => {}
+                None => {
+                    return Err(LoadError::new(
+                        line,
+                        *column,
+                        ErrorKind::ExtraColumn(field.to_string()),
+                    ))
+                }
+            }
+        }
+
+        let record = csv_record(&values, &columns, line)?;
+        jobs.push((record.into_job(line, &columns)?, line, 1));
+    }
+
+    Ok(jobs)
+}
+
+/// Parses the fields of a CSV row, `columns` locate the errors.
+fn csv_record(
+    values: &[Option<&str>; FIELDS.len()],
+    columns: &Columns,
+    line: usize,
+) -> Result<Record, LoadError> {
+    let value = |field: &str| values[FIELDS.iter().position(|x| *x == field).unwrap()];
+
+    let numbers = |field: &'static str| -> Result<Option<Vec<u32>>, LoadError> {
+        value(field)
+            .map(|list| {
+                list.split_whitespace()
+                    .map(|x| {
+                        x.parse::<u32>().map_err(|_| {
+                            LoadError::new(
+                                line,
+                                column_of(columns, field),
+                                ErrorKind::InvalidValue {
+                                    field,
+                                    value: list.to_string(),
+                                    reason: "not a list of numbers".to_string(),
+                                },
+                            )
+                        })
+                    })
+                    .collect()
+            })
+            .transpose()
+    };
+
+    let required = |field: &'static str| {
+        value(field).ok_or(LoadError::new(
+            line,
+            column_of(columns, field),
+            ErrorKind::MissingField(field),
+        ))
+    };
+
+    Ok(Record {
+        name: required("name")?.to_string(),
+        arrival_time: number(
+            Some(required("arrival_time")?),
+            "arrival_time",
+            line,
+            columns,
+        )?
+        .unwrap(),
+        service_time: number(value("service_time"), "service_time", line, columns)?,
+        priority: number(value("priority"), "priority", line, columns)?,
+        class: value("class").map(str::to_string),
+        owner: value("owner").map(str::to_string),
+        tickets: number(value("tickets"), "tickets", line, columns)?,
+        nice: number(value("nice"), "nice", line, columns)?,
+        affinity: numbers("affinity")?
+            .map(|cpus| cpus.into_iter().map(|cpu| cpu as usize).collect()),
+        bursts: numbers("bursts")?,
+        deadline: number(value("deadline"), "deadline", line, columns)?,
+        predecessors: value("predecessors")
+            .map(|names| names.split_whitespace().map(str::to_string).collect()),
+    })
+}
+
+fn number<T: std::str::FromStr>(
+    value: Option<&str>,
+    field: &'static str,
+    line: usize,
+    columns: &Columns,
+) -> Result<Option<T>, LoadError> {
+    value
+        .map(|value| {
+            value.parse::<T>().map_err(|_| {
+                LoadError::new(
+                    line,
+                    column_of(columns, field),
+                    ErrorKind::InvalidValue {
+                        field,
+                        value: value.to_string(),
+                        reason: "not a number".to_string(),
+                    },
+                )
+            })
+        })
+        .transpose()
+}
+
+/// CSV columns of a periodic task.
+const TASK_FIELDS: [&str; 4] = ["name", "period", "wcet", "deadline"];
+
+/// Reads the periodic tasks of the CSV file at `path`.
+pub fn load_tasks(path: &Path) -> Result<Vec<PeriodicTask>, LoadError> {
+    let text = fs::read_to_string(path)
+        .map_err(|why| LoadError::new(0, 0, ErrorKind::Io(format!("couldn't open it: {}", why))))?;
+
+    parse_tasks(&text)
+}
+
+/// `name,period,wcet[,deadline]` rows, the deadline is the period if it is empty.
+/// Blank lines and lines starting with `#` are skipped. Every name has to be unique
+/// and the hyperperiod has to fit in a `u32` and release at most
+/// `realtime::MAX_INSTANCES` instances.
+pub fn parse_tasks(text: &str) -> Result<Vec<PeriodicTask>, LoadError> {
+    let mut tasks = Vec::new();
+    let mut names = HashSet::new();
+
+    for (idx, row) in text.lines().enumerate() {
+        let line = idx + 1;
+
+        if row.trim().is_empty() || row.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let fields = split_row(row);
+        if let Some((field, column)) = fields[TASK_FIELDS.len().min(fields.len())..]
+            .iter()
+            .find(|(field, _)| !field.is_empty())
+        {
+            return Err(LoadError::new(
+                line,
+                *column,
+                ErrorKind::ExtraColumn(field.to_string()),
+            ));
+        }
+
+        // a missing field is reported at the end of the row
+        let end = row.chars().count() + 1;
+        let value = |idx: usize| {
+            fields
+                .get(idx)
+                .filter(|(field, _)| !field.is_empty())
+                .copied()
+        };
+        let required = |idx: usize| {
+            value(idx).ok_or(LoadError::new(
+                line,
+                fields.get(idx).map_or(end, |&(_, column)| column),
+                ErrorKind::MissingField(TASK_FIELDS[idx]),
+            ))
+        };
+        let number = |(value, column): (&str, usize), idx: usize| {
+            value.parse::<u32>().map_err(|_| {
+                LoadError::new(
+                    line,
+                    column,
+                    ErrorKind::InvalidValue {
+                        field: TASK_FIELDS[idx],
+                        value: value.to_string(),
+                        reason: "not a number".to_string(),
+                    },
+                )
+            })
+        };
+
+        let (name, column) = required(0)?;
+        let mut task = PeriodicTask::new(name, number(required(1)?, 1)?, number(required(2)?, 2)?);
+        if let Some(deadline) = value(3) {
+            task.deadline = number(deadline, 3)?;
+        }
+
+        if let Err(why) = task.check() {
+            return Err(LoadError::new(
+                line,
+                column,
+                ErrorKind::Task(why.to_string()),
+            ));
+        }
+        if !names.insert(task.name.clone()) {
+            return Err(LoadError::new(
+                line,
+                column,
+                ErrorKind::DuplicateName(task.name),
+            ));
+        }
+
+        tasks.push(task);
+    }
+
+    if let Err(why) = checked_hyperperiod(&tasks) {
+        return Err(LoadError::new(0, 0, ErrorKind::Task(why.to_string())));
+    }
+
+    Ok(tasks)
+}
+
+#[derive(Deserialize)]
+struct JsonFile {
+    jobs: Vec<Record>,
+}
+
+/// An array of job objects, or an object with a `jobs` array, using the field names
+/// of `Job`: `[{"name": "A", "arrival_time": 0, "service_time": 5}]`.
+fn parse_json(text: &str) -> Result<Vec<(Job, usize, usize)>, LoadError> {
+    let records = match text.trim_start().starts_with('[') {
+        true => serde_json::from_str::<Vec<Record>>(text),
+        false => serde_json::from_str::<JsonFile>(text).map(|file| file.jobs),
+    }
+    .map_err(|why| LoadError::new(why.line(), why.column(), ErrorKind::Syntax(why.to_string())))?;
+
+    records
+        .into_iter()
+        .zip(json_objects(text))
+        .map(|(record, (line, column))| {
+            let columns: Columns = [column; FIELDS.len()];
+            Ok((record.into_job(line, &columns)?, line, column))
+        })
+        .collect()
+}
+
+/// Line and column of every job object in a JSON file, the objects directly inside
+/// the outermost array.
+fn json_objects(text: &str) -> Vec<(usize, usize)> {
+    let mut starts = Vec::new();
+    let mut depth = 0;
+    let mut array_depth = None;
+    let mut in_string = false;
+    let mut escaped = false;
+    let (mut line, mut column) = (1, 0);
+
+    for c in text.chars() {
+        column += 1;
+
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '\n' => {
+                line += 1;
+                column = 0;
+            }
+            '"' => in_string = true,
+            '[' | '{' => {
+                if c == '{' && array_depth == Some(depth) {
+                    starts.push((line, column));
+                }
+                depth += 1;
+                if c == '[' && array_depth.is_none() {
+                    array_depth = Some(depth);
+                }
+            }
+            ']' | '}' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    starts
+}
+
+#[derive(Deserialize)]
+struct TomlFile {
+    jobs: Vec<toml::Spanned<Record>>,
+}
+
+/// A `[[jobs]]` table for every job, using the field names of `Job`.
+fn parse_toml(text: &str) -> Result<Vec<(Job, usize, usize)>, LoadError> {
+    let located = |span: Option<Range<usize>>| match span {
+        Some(span) => position(text, span.start),
+        None => (0, 0),
+    };
+
+    let file = toml::from_str::<TomlFile>(text).map_err(|why| {
+        let (line, column) = located(why.span());
+        LoadError::new(line, column, ErrorKind::Syntax(why.message().to_string()))
+    })?;
+
+    file.jobs
+        .into_iter()
+        .map(|record| {
+            let (line, column) = located(Some(record.span()));
+            let columns: Columns = [column; FIELDS.len()];
+            Ok((record.into_inner().into_job(line, &columns)?, line, column))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        loader::{load, load_tasks, parse, parse_tasks, ErrorKind, Format, LoadError},
+        Burst, JobClass,
+    };
+    use std::path::Path;
+
+    #[test]
+    fn test() {
+        let text = "# the jobs of the lab\n\
+                    name, arrival, service, priority, owner, bursts, deadline\n\
+                    A,0,5,3,alice,,\n\
+                    \n\
+                    B,1,,,bob,2 3 1,12,\n";
+        let jobs = parse(text, Format::Csv).unwrap();
+
+        assert_eq!(jobs.len(), 2);
+        assert_eq!((jobs[0].priority, jobs[0].service_time), (3, 5));
+        assert_eq!(jobs[1].owner.as_deref(), Some("bob"));
+        assert_eq!(jobs[1].bursts, Burst::alternating(&[2, 3, 1]));
+        assert_eq!((jobs[1].service_time, jobs[1].deadline), (3, Some(12)));
+
+        // without a header the columns keep their order, a trailing comma is fine
+        let jobs = parse("A,0,4,\nB,1,3,2,system,\n", Format::Csv).unwrap();
+        assert_eq!(jobs[1].class, JobClass::System);
+
+        let json = r#"[
+            {"name": "A", "arrival_time": 0, "service_time": 5},
+            {"name": "B", "arrival": 1, "bursts": [2, 3, 1], "class": "interactive"}
+        ]"#;
+        let jobs = parse(json, Format::Json).unwrap();
+        assert_eq!(
+            (jobs[1].service_time, jobs[1].class),
+            (3, JobClass::Interactive)
+        );
+
+        let toml = "[[jobs]]\nname = \"A\"\narrival_time = 0\nservice_time = 5\n\n\
+                    [[jobs]]\nname = \"B\"\narrival_time = 2\nservice_time = 1\naffinity = [1]\n";
+        let jobs = parse(toml, Format::Toml).unwrap();
+        assert_eq!(jobs[1].affinity, vec![1]);
+
+        let jobs = parse(
+            "name,arrival,service,predecessors\nA,0,2,\nB,0,3,\nC,1,1,A B\n",
+            Format::Csv,
+        )
+        .unwrap();
+        assert_eq!(jobs[2].predecessors, vec!["A", "B"]);
+        let json = r#"[{"name": "A", "arrival_time": 0, "service_time": 2, "predecessors": []},
+                       {"name": "B", "arrival_time": 0, "service_time": 3, "predecessors": ["A"]}]"#;
+        assert_eq!(
+            parse(json, Format::Json).unwrap()[1].predecessors,
+            vec!["A"]
+        );
+
+        // the data files hold the same jobs in every format
+        let times = |path: &str| {
+            load(Path::new(path))
+                .unwrap()
+                .iter()
+                .map(|job| (job.name.clone(), job.arrival_time, job.service_time))
+                .collect::<Vec<(String, u32, u32)>>()
+        };
+        let csv = times("./data/scheduler_test.csv");
+        assert_eq!(csv.len(), 6);
+        assert_eq!(csv, times("./data/scheduler_test.json"));
+        assert_eq!(csv, times("./data/scheduler_test.toml"));
+        assert_eq!(
+            load(Path::new("./data/io_test.csv")).unwrap()[2]
+                .bursts
+                .len(),
+            3
+        );
+
+        let tasks = parse_tasks("# name,period,wcet\nT1,5,2\n\nT2,7,4,6,\n").unwrap();
+        assert_eq!((tasks[0].name.as_str(), tasks[0].deadline), ("T1", 5));
+        assert_eq!(
+            (tasks[1].period, tasks[1].wcet, tasks[1].deadline),
+            (7, 4, 6)
+        );
+        assert_eq!(
+            load_tasks(Path::new("./data/periodic_test.csv"))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |text: &str, format: Format| parse(text, format).unwrap_err();
+
+        assert_eq!(
+            error("A,0,5\nB,x,3\n", Format::Csv),
+            LoadError {
+                line: 2,
+                column: 3,
+                kind: ErrorKind::InvalidValue {
+                    field: "arrival_time",
+                    value: "x".to_string(),
+                    reason: "not a number".to_string(),
+                },
+            }
+        );
+        assert_eq!(
+            error("A,0,5\n# B\nA,2,3\n", Format::Csv).kind,
+            ErrorKind::DuplicateName("A".to_string())
+        );
+        assert_eq!(error("A,0,5\n# B\nA,2,3\n", Format::Csv).line, 3);
+
+        let zero = error("A,0,5\nB,1,0\n", Format::Csv);
+        assert_eq!((zero.line, zero.column), (2, 5));
+        assert_eq!(zero.kind, ErrorKind::ZeroServiceTime("B".to_string()));
+
+        assert_eq!(
+            error("A,0,5,,,,,,,,,,9\n", Format::Csv).kind,
+            ErrorKind::ExtraColumn("9".to_string())
+        );
+        let bursts = error("A,0,5,,,,,,2 3 1\n", Format::Csv);
+        assert_eq!((bursts.line, bursts.column), (1, 5));
+        assert_eq!(
+            bursts.to_string(),
+            "line 1, column 5: invalid service_time \"5\": the CPU bursts add up to 3"
+        );
+        assert!(matches!(
+            error("A,0,5,,,2000000\n", Format::Csv).kind,
+            ErrorKind::InvalidValue {
+                field: "tickets",
+                ..
+            }
+        ));
+        assert_eq!(
+            error("A,0\n", Format::Csv).kind,
+            ErrorKind::MissingField("service_time")
+        );
+
+        let json = "[\n  {\"name\": \"A\", \"arrival_time\": 0, \"service_time\": 5},\n  {\"name\": \"A\", \"arrival_time\": 1, \"service_time\": 2}\n]";
+        let duplicate = error(json, Format::Json);
+        assert_eq!((duplicate.line, duplicate.column), (3, 3));
+
+        let broken = error("[{\"name\": \"A\",\n \"arrival_time\": -1}]", Format::Json);
+        assert_eq!(broken.line, 2);
+
+        let unknown = error("A,0,5\nB,1,3,,,,,,,,C\n", Format::Csv);
+        assert_eq!((unknown.line, unknown.column), (2, 1));
+        assert_eq!(
+            unknown.kind,
+            ErrorKind::Dependency("B waits for C, which is not a job".to_string())
+        );
+        assert_eq!(
+            error(
+                "A,0,5,,,,,,,,C\nB,1,3,,,,,,,,A\nC,2,1,,,,,,,,B\n",
+                Format::Csv
+            )
+            .to_string(),
+            "line 1, column 1: dependency cycle A -> B -> C -> A"
+        );
+
+        let task_error = |text: &str| parse_tasks(text).unwrap_err();
+        let wcet = task_error("T1,5,2\nT2,7,x\n");
+        assert_eq!((wcet.line, wcet.column), (2, 6));
+        assert_eq!(task_error("T1,5\n").kind, ErrorKind::MissingField("wcet"));
+        assert_eq!(
+            task_error("T1,5,2,4,1\n").kind,
+            ErrorKind::ExtraColumn("1".to_string())
+        );
+        assert_eq!(
+            task_error("T1,5,2\nT2,5,3,2\n").to_string(),
+            "line 2, column 1: T2 needs 0 < wcet <= deadline <= period"
+        );
+        assert_eq!(
+            task_error("T1,5,2\nT1,7,4\n").kind,
+            ErrorKind::DuplicateName("T1".to_string())
+        );
+        assert_eq!(task_error("T1,65521,1\nT2,65519,1\nT3,65537,1\n").line, 0);
+        assert_eq!(
+            task_error("T1,1,1\nT2,4000000000,1\n").to_string(),
+            "the hyperperiod releases 4000000001 instances, more than 1000000"
+        );
+
+        assert_eq!(
+            error("A,4294967290,10\n", Format::Csv).to_string(),
+            "line 1, column 1: A may finish after tick 4294967295"
+        );
+        assert_eq!(
+            error("A,0,3000000000\nB,0,3000000000\n", Format::Csv).kind,
+            ErrorKind::Horizon("B".to_string())
+        );
+        assert_eq!(
+            error("A,0,,,,,,,4294967295 1 1\n", Format::Csv).to_string(),
+            "line 1, column 11: invalid bursts \"[4294967295, 1, 1]\": add up to more than 4294967295 ticks"
+        );
+
+        let toml = "[[jobs]]\nname = \"A\"\narrival_time = 0\nservice_time = 0\n";
+        assert_eq!(
+            error(toml, Format::Toml).to_string(),
+            "line 1, column 1: A has no service time"
+        );
+    }
+}
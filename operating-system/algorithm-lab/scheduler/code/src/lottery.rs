@@ -0,0 +1,205 @@
+use std::collections::BTreeSet;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    share::ShareReport,
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+pub struct LotteryScheduler {
+    jobs: Vec<Job>,
+    share: ShareReport,
+}
+
+/// Tickets of every job in job order, a Fenwick tree so a draw finds its winner
+/// without walking all the competing jobs. The sums are `u64`, a job may hold up to
+/// `STRIDE1` tickets.
+#[derive(Debug, Clone)]
+struct Tickets {
+    tree: Vec<u64>,
+}
+
+impl Tickets {
+    fn new(task_len: usize) -> Tickets {
+        Tickets {
+            tree: vec![0; task_len + 1],
+        }
+    }
+
+    fn add(&mut self, idx: usize, tickets: u32) {
+        let mut pos = idx + 1;
+        while pos < self.tree.len() {
+            self.tree[pos] += tickets as u64;
+            pos += pos & pos.wrapping_neg();
+        }
+    }
+
+    fn remove(&mut self, idx: usize, tickets: u32) {
+        let mut pos = idx + 1;
+        while pos < self.tree.len() {
+            self.tree[pos] -= tickets as u64;
+            pos += pos & pos.wrapping_neg();
+        }
+    }
+
+    fn total(&self) -> u64 {
+        let mut pos = self.tree.len() - 1;
+        let mut total = 0;
+        while pos > 0 {
+            total += self.tree[pos];
+            pos -= pos & pos.wrapping_neg();
+        }
+        total
+    }
+
+    /// The job holding `ticket`, counting the tickets of the jobs in order.
+    fn winner(&self, mut ticket: u64) -> usize {
+        let mut pos = 0;
+        let mut step = (self.tree.len() - 1).next_power_of_two();
+
+        while step > 0 {
+            if pos + step < self.tree.len() && self.tree[pos + step] <= ticket {
+                pos += step;
+                ticket -= self.tree[pos];
+            }
+            step /= 2;
+        }
+
+        pos
+    }
+}
+
+/// Draws a winner among the competing jobs, the running one included, at the start
+/// of every quantum.
+struct LotteryQueue {
+    quantum: u32,
+    rng: StdRng,
+    tickets: Tickets,
+    /// Jobs that arrived and have not finished or blocked.
+    competing: BTreeSet<usize>,
+    running: Option<usize>,
+    share: ShareReport,
+}
+
+impl Policy for LotteryQueue {
+    fn enqueue(&mut self, job: usize, ctx: &mut Context) {
+        self.competing.insert(job);
+        self.tickets.add(job, ctx.jobs[job].tickets);
+        self.share.join(job);
+    }
+
+    // DRAW: a new winner at the start of every quantum
+    fn pick(&mut self, _ctx: &mut Context) -> Option<Pick> {
+        let total = self.tickets.total();
+        if total == 0 {
+            return None;
+        }
+
+        let winner = self.rng.gen_range(0..total);
+        let job = self.tickets.winner(winner);
+        self.running = Some(job);
+
+        let detail = format!("ticket {} of {}", winner, total);
+        Some(Pick::new(job).slice(self.quantum).detail(detail))
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        self.competing.iter().copied().collect()
+    }
+
+    fn len(&self) -> usize {
+        self.competing.len() - self.running.iter().count()
+    }
+
+    fn ran(&mut self, job: usize, ticks: u32, _ctx: &mut Context) {
+        self.share.ran(job, ticks);
+    }
+
+    fn expired(&mut self, _job: usize, _ctx: &mut Context) {
+        self.running = None;
+    }
+
+    fn done(&mut self, job: usize, ctx: &mut Context) {
+        self.running = None;
+        self.competing.remove(&job);
+        self.tickets.remove(job, ctx.jobs[job].tickets);
+        self.share.leave(job);
+    }
+}
+
+impl Scheduler for LotteryScheduler {
+    fn new(mut jobs: Vec<Job>) -> LotteryScheduler {
+        assert!(jobs.iter().all(|job| job.tickets > 0), "every job needs a ticket");
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        LotteryScheduler {
+            share: ShareReport::new(&jobs),
+            jobs,
+        }
+    }
+
+    /// Draws a winner every `config.quantum` ticks, the same `config.seed` draws the
+    /// same tickets.
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("Lottery（彩票调度）");
+
+        let mut queue = LotteryQueue {
+            quantum: config.quantum,
+            rng: StdRng::seed_from_u64(config.seed),
+            tickets: Tickets::new(self.jobs.len()),
+            competing: BTreeSet::new(),
+            running: None,
+            share: ShareReport::new(&self.jobs),
+        };
+        Engine::new(&mut self.jobs, config).run(&mut queue, &mut schedule);
+        self.share = queue.share;
+
+        schedule.note(format!(
+            "seed: {}, quantum: {}",
+            config.seed, config.quantum
+        ));
+        schedule.note(self.share.to_string());
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        build_jobs,
+        lottery::{LotteryScheduler, Tickets},
+        stride::STRIDE1,
+        Job, Schedule, Scheduler, SchedulerConfig,
+    };
+
+    #[test]
+    fn test() {
+        let jobs: Vec<Job> = build_jobs();
+        let config = SchedulerConfig::default().seed(42).quantum(2);
+
+        let schedule = LotteryScheduler::new(jobs.clone()).run(&config);
+        schedule.print();
+
+        // the same seed gives the same schedule
+        let again = LotteryScheduler::new(jobs).run(&config);
+
+        let finish_times = |schedule: &Schedule| {
+            ["A", "B", "C", "D", "E"]
+                .iter()
+                .map(|name| schedule.finish_time(name).unwrap())
+                .collect::<Vec<u32>>()
+        };
+        assert_eq!(finish_times(&schedule), finish_times(&again));
+        assert_eq!(finish_times(&schedule).into_iter().max(), Some(25));
+
+        // 5000 jobs with the most tickets hold more than a u32 of them
+        let mut tickets = Tickets::new(5000);
+        for idx in 0..5000 {
+            tickets.add(idx, STRIDE1 as u32);
+        }
+        assert_eq!(tickets.total(), 5000 * STRIDE1);
+        assert_eq!(tickets.winner(4999 * STRIDE1), 4999);
+    }
+}
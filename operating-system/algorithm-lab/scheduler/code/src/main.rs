@@ -1,20 +1,57 @@
-use clearscreen;
-use scheduler::{Job, fcfs::FcfsScheduler, sjf::SjfScheduler, rr::RrScheduler};
-use std::{
-    fs::File,
-    io::{stdin, BufRead, BufReader},
-    path::Path,
+use clap::Parser;
+use cli::{Cli, Command};
+use scheduler::{
+    cfs::CfsScheduler,
+    compare::Comparison,
+    fcfs::FcfsScheduler,
+    gantt::Gantt,
+    hrrn::HrrnScheduler,
+    loader,
+    lottery::LotteryScheduler,
+    mlfq::{LastLevelPolicy, MlfqScheduler},
+    mlq::{InterQueuePolicy, MlqScheduler, QueuePolicy},
+    priority::{PriorityMode, PriorityScheduler, TieBreak},
+    realtime::{PeriodicTask, RealtimePolicy, RealtimeScheduler},
+    report,
+    rr::RrScheduler,
+    sjf::SjfScheduler,
+    smp::{self, Balancing, QueueMode, SmpScheduler},
+    srtf::SrtfScheduler,
+    stride::{StrideScheduler, STRIDE1},
+    workload::{self, Arrivals, ServiceTimes, Workload},
+    Burst, Job, JobClass, Schedule, Scheduler, SchedulerConfig,
 };
-use scheduler::Scheduler;
+use std::{fs, io::stdin, path::Path, process::ExitCode, str::FromStr};
+
+mod cli;
+
 fn clr() {
     clearscreen::clear().expect("failed to clear screen");
 }
 
+/// Asks until the answer is whitespace separated values that `valid` accepts.
+fn read_values<T: FromStr>(prompt: &str, valid: impl Fn(&[T]) -> bool) -> Vec<T> {
+    println!("{}", prompt);
+    let mut line = String::new();
+    stdin().read_line(&mut line).unwrap();
+
+    let values = line
+        .split_whitespace()
+        .map(|x| x.parse::<T>())
+        .collect::<Result<Vec<T>, _>>();
+
+    match values {
+        Ok(values) if valid(&values) => values,
+        _ => {
+            println!("Invalid input. Please try again.");
+            read_values(prompt, valid)
+        }
+    }
+}
+
 fn select_algo() -> u32 {
-    clr();
     print!(
-        "{}{}{}{}{}",
-        "Now you can run the following algorithms:\n", "1. FCFS\n", "2. SJF\n", "3. RR\n", "4. FCFS+RR\n",
+        "Now you can run the following algorithms:\n1. FCFS\n2. SJF\n3. RR\n4. Compare\n5. SRTF\n6. Priority\n7. HRRN\n8. MLFQ\n9. MLQ\n10. Lottery\n11. Stride\n12. CFS\n13. EDF / RM (periodic tasks)\n14. Multiprocessor\n",
     );
 
     let mut algo_select = String::new();
@@ -22,21 +59,22 @@ fn select_algo() -> u32 {
 
     match algo_select.trim().parse::<u32>() {
         Ok(i) => {
-            if i > 4 || i < 1 {
+            if !(1..=14).contains(&i) {
                 println!("Invalid select. Please try again.");
                 return select_algo();
             }
 
-            return i;
+            i
         }
         Err(..) => {
             println!("Invalid select. Please try again.");
-            return select_algo();
+            select_algo()
         }
-    };
+    }
 }
 
-fn read_from_file() -> Vec<Job> {
+/// Jobs from a file whose affinity names only CPUs below `cpus`.
+fn read_from_file(cpus: usize) -> Vec<Job> {
     let mut file_path = String::new();
     println!("Input the file path: (Empty means using default config)");
     let default_path = "./data/scheduler_test.csv".to_string();
@@ -46,45 +84,54 @@ fn read_from_file() -> Vec<Job> {
         Err(..) => file_path = default_path,
     }
 
-    if file_path.len() == 0 {
+    if file_path.is_empty() {
         file_path = "./data/scheduler_test.csv".to_string()
     }
 
-    println!("{}", file_path.len());
-
     let file_path = Path::new(file_path.trim());
 
-    let file = match File::open(file_path) {
-        Err(why) => panic!("couldn't open {}: {}", file_path.display(), why),
-        Ok(file) => file,
-    };
+    // CSV, JSON or TOML by the extension, see `scheduler::loader`
+    let jobs = loader::load(file_path)
+        .map_err(|why| why.to_string())
+        .and_then(|jobs| {
+            smp::check_affinity(&jobs, cpus)
+                .map(|_| jobs)
+                .map_err(|why| why.to_string())
+        });
+
+    match jobs {
+        Ok(jobs) => jobs,
+        Err(why) => {
+            println!("{}: {}", file_path.display(), why);
+            println!("Invalid input. Please try again.");
+            read_from_file(cpus)
+        }
+    }
+}
 
-    let mut ret = Vec::<Job>::new();
-
-    for line in BufReader::new(file).lines() {
-        if let Ok(line) = line {
-            let mut line = line.split(',');
-            let name = line.next().unwrap();
-            let arrival_time = line.next().unwrap().parse::<u32>().unwrap();
-            let service_time = line.next().unwrap().parse::<u32>().unwrap();
-            let job = Job {
-                name: name.to_string(),
-                arrival_time,
-                service_time,
-                finish_time: None,
-                whole_time: None,
-                weight_whole_time: None,
-                served_time: 0,
-            };
+fn read_periodic_tasks() -> Vec<PeriodicTask> {
+    let mut file_path = String::new();
+    println!("Input the periodic task file path: name,period,wcet[,deadline] (Empty means using default config)");
+    stdin().read_line(&mut file_path).unwrap();
+
+    let file_path = match file_path.trim() {
+        "" => "./data/periodic_test.csv",
+        file_path => file_path,
+    };
+    let file_path = Path::new(file_path);
 
-            ret.push(job);
+    match loader::load_tasks(file_path) {
+        Ok(tasks) => tasks,
+        Err(why) => {
+            println!("{}: {}", file_path.display(), why);
+            println!("Invalid input. Please try again.");
+            read_periodic_tasks()
         }
     }
-
-    ret
 }
 
-fn read_from_cmd() -> Vec<Job> {
+/// Jobs typed in whose affinity names only CPUs below `cpus`.
+fn read_from_cmd(cpus: usize) -> Vec<Job> {
     clr();
 
     let mut jobs = Vec::<Job>::new();
@@ -98,23 +145,71 @@ fn read_from_cmd() -> Vec<Job> {
             break;
         }
 
-        let mut arrival_time = String::new();
-        println!("Input the arrival time: ");
-        stdin().read_line(&mut arrival_time).unwrap();
+        let arrival_time = read_values::<u32>("Input the arrival time: ", |x| x.len() == 1)[0];
+        let service_time =
+            read_values::<u32>("Input the service time: ", |x| x.len() == 1 && x[0] > 0)[0];
+        let priority =
+            read_values::<u32>("Input the priority: (Empty means 0, the highest)", |x| {
+                x.len() <= 1
+            });
 
-        let mut service_time = String::new();
-        println!("Input the service time: ");
-        stdin().read_line(&mut service_time).unwrap();
+        let mut job = Job::new(name.trim(), arrival_time, service_time);
 
-        let job = Job {
-            name: name.trim().to_string(),
-            arrival_time: arrival_time.trim().parse::<u32>().unwrap(),
-            service_time: service_time.trim().parse::<u32>().unwrap(),
-            finish_time: None,
-            whole_time: None,
-            weight_whole_time: None,
-            served_time: 0,
-        };
+        if let [priority] = priority[..] {
+            job.priority = priority;
+        }
+
+        let class = read_values::<JobClass>(
+            "Input the class: system/interactive/batch (Empty means batch)",
+            |x| x.len() <= 1,
+        );
+
+        if let [class] = class[..] {
+            job.class = class;
+        }
+
+        let tickets = read_values::<u32>("Input the tickets: (Empty means 100)", |x| {
+            x.len() <= 1
+                && x.iter()
+                    .all(|&tickets| tickets > 0 && tickets as u64 <= STRIDE1)
+        });
+
+        if let [tickets] = tickets[..] {
+            job.tickets = tickets;
+        }
+
+        let nice = read_values::<i32>("Input the nice value: -20 ~ 19 (Empty means 0)", |x| {
+            x.len() <= 1 && x.iter().all(|nice| (-20..=19).contains(nice))
+        });
+
+        if let [nice] = nice[..] {
+            job.nice = nice;
+        }
+
+        job.affinity = read_values::<usize>(
+            &format!(
+                "Input the CPUs it may run on: 0 ~ {} (e.g. 0 1, Empty means any)",
+                cpus - 1
+            ),
+            |x| x.iter().all(|&cpu| cpu < cpus),
+        );
+
+        // the CPU bursts, every other one from the first, add up to the service time
+        let service_time = job.service_time;
+        let ticks = read_values::<u32>(
+            "Input the CPU and I/O bursts adding up to the service time: (e.g. 2 3 1, Empty means one CPU burst)",
+            |x| {
+                x.is_empty()
+                    || (x.len() % 2 == 1
+                        && !x.contains(&0)
+                        && x.iter().step_by(2).sum::<u32>() == service_time)
+            },
+        );
+
+        if !ticks.is_empty() {
+            job.bursts =
+                Job::with_bursts(&job.name, job.arrival_time, Burst::alternating(&ticks)).bursts;
+        }
 
         jobs.push(job);
     }
@@ -122,79 +217,488 @@ fn read_from_cmd() -> Vec<Job> {
     jobs
 }
 
-fn read_data() -> Vec<Job> {
-    println!("Now we need you to input data, select one way:\n1.Read from file\n2.Input interactively");
+fn read_queue_policy(queue: &str) -> QueuePolicy {
+    println!("Policy of the {} queue: 1. FCFS 2. SJF 3. RR", queue);
+    let mut policy = String::new();
+    stdin().read_line(&mut policy).unwrap();
+
+    match policy.trim() {
+        "1" => QueuePolicy::Fcfs,
+        "2" => QueuePolicy::Sjf,
+        "3" => {
+            println!("Enter rr slice num: ");
+            let mut num = String::new();
+            stdin().read_line(&mut num).unwrap();
+            match num.trim().parse::<u32>() {
+                Ok(num) if num > 0 => QueuePolicy::Rr(num),
+                _ => {
+                    println!("Invalid input. Please try again.");
+                    read_queue_policy(queue)
+                }
+            }
+        }
+        _ => {
+            println!("Invalid input. Please try again.");
+            read_queue_policy(queue)
+        }
+    }
+}
+
+fn read_config() -> SchedulerConfig {
+    println!("Enter the quantum and the seed: (Empty means 1 0)");
+    let mut numbers = String::new();
+    stdin().read_line(&mut numbers).unwrap();
+    let numbers = numbers
+        .split_whitespace()
+        .map(|x| x.parse::<u64>())
+        .collect::<Result<Vec<u64>, _>>();
+    // the quantum is a positive u32, the seed any u64
+    let to_quantum = |x: &u64| u32::try_from(*x).ok().filter(|&quantum| quantum > 0);
+
+    let config = match numbers.as_deref() {
+        Ok([]) => Some(SchedulerConfig::default()),
+        Ok([quantum]) => {
+            to_quantum(quantum).map(|quantum| SchedulerConfig::default().quantum(quantum))
+        }
+        Ok([quantum, seed]) => to_quantum(quantum)
+            .map(|quantum| SchedulerConfig::default().quantum(quantum).seed(*seed)),
+        _ => None,
+    };
+
+    config.unwrap_or_else(|| {
+        println!("Invalid input. Please try again.");
+        read_config()
+    })
+}
+
+/// The service times of the distribution `choice`, `None` for the default one.
+fn read_service_times(choice: u32) -> Option<ServiceTimes> {
+    let numbers = read_values::<f64>(
+        match choice {
+            2 => "Enter the min and the max service time: (e.g. 1 10)",
+            3 => "Enter the short and the long service time and the share of long jobs: (e.g. 2 20 0.1)",
+            _ => "Enter the mean service time: (Empty means 5)",
+        },
+        |_| true,
+    );
+
+    match (choice, &numbers[..]) {
+        (1, []) => None,
+        (1, [mean]) if *mean > 0.0 => Some(ServiceTimes::Exponential { mean: *mean }),
+        (2, [min, max]) if 1.0 <= *min && min <= max => Some(ServiceTimes::Uniform {
+            min: *min as u32,
+            max: *max as u32,
+        }),
+        (3, [short, long, long_share])
+            if *short >= 1.0 && *long >= 1.0 && (0.0..=1.0).contains(long_share) =>
+        {
+            Some(ServiceTimes::Bimodal {
+                short: *short as u32,
+                long: *long as u32,
+                long_share: *long_share,
+            })
+        }
+        _ => {
+            println!("Invalid input. Please try again.");
+            read_service_times(choice)
+        }
+    }
+}
+
+fn read_workload() -> Vec<Job> {
+    println!("Enter the number of jobs, the load factor and the seed: (Empty means 10 0.8 0)");
+    let mut numbers = String::new();
+    stdin().read_line(&mut numbers).unwrap();
+    let numbers = numbers.split_whitespace().collect::<Vec<&str>>();
+
+    let mut workload = Workload::new(10);
+    if let [jobs, load, seed] = numbers[..] {
+        match (
+            jobs.parse::<usize>(),
+            load.parse::<f64>(),
+            seed.parse::<u64>(),
+        ) {
+            (Ok(jobs), Ok(load), Ok(seed)) if load > 0.0 => {
+                workload = Workload::new(jobs).load(load).seed(seed)
+            }
+            _ => {
+                println!("Invalid input. Please try again.");
+                return read_workload();
+            }
+        }
+    } else if !numbers.is_empty() {
+        println!("Invalid input. Please try again.");
+        return read_workload();
+    }
+
+    println!("Arrivals: 1. Poisson 2. Uniform (default 1): ");
+    let mut arrivals = String::new();
+    stdin().read_line(&mut arrivals).unwrap();
+    workload = workload.arrivals(match arrivals.trim() {
+        "2" => Arrivals::Uniform,
+        _ => Arrivals::Poisson,
+    });
+
+    println!("Service times: 1. Exponential 2. Uniform 3. Bimodal (default 1): ");
+    let mut choice = String::new();
+    stdin().read_line(&mut choice).unwrap();
+    let choice = match choice.trim() {
+        "2" => 2,
+        "3" => 3,
+        _ => 1,
+    };
+
+    if let Some(service_times) = read_service_times(choice) {
+        workload = workload.service_times(service_times);
+    }
+
+    let jobs = workload.generate();
+
+    println!("Save the jobs to: (e.g. data/generated.csv, Empty means not saving)");
+    let mut file_path = String::new();
+    stdin().read_line(&mut file_path).unwrap();
+
+    let file_path = file_path.trim();
+    if !file_path.is_empty() {
+        match fs::write(file_path, workload::to_csv(&jobs)) {
+            Ok(..) => println!("Saved to {}", file_path),
+            Err(why) => println!("couldn't write {}: {}", file_path, why),
+        }
+    }
+
+    jobs
+}
+
+/// Jobs that may only run on CPUs below `cpus`.
+fn read_data(cpus: usize) -> Vec<Job> {
+    println!("Now we need you to input data, select one way:\n1.Read from file\n2.Input interactively\n3.Generate a workload");
     let mut method = String::new();
     stdin().read_line(&mut method).unwrap();
 
     match method.trim().parse::<u32>() {
         Ok(i) => {
-            if i > 2 || i < 1 {
+            if !(1..=3).contains(&i) {
                 println!("Invalid input. Please try again.");
-                return read_data();
+                return read_data(cpus);
             }
 
-            if i == 1 {
-                return read_from_file();
-            } else {
-                return read_from_cmd();
+            match i {
+                1 => read_from_file(cpus),
+                2 => read_from_cmd(cpus),
+                _ => read_workload(),
             }
         }
         Err(..) => {
             println!("Invalid input. Please try again.");
-            return read_data();
+            read_data(cpus)
         }
-    };
+    }
 }
 
-fn main() {
-    clr();
-    println!(
-        "{}",
-        "Welcome to Operating System Algorithms written in Rust.\n"
-    );
+fn read_cpus() -> usize {
+    let cpus = read_values::<usize>("Enter the number of CPUs: (Empty means 2)", |x| {
+        x.len() <= 1 && !x.contains(&0)
+    });
 
-    let jobs = read_data();
-    let algo = select_algo();
+    cpus.first().copied().unwrap_or(2)
+}
 
+/// Builds the selected schedulers, asking for the options only they have. SMP runs
+/// on `cpus` processors.
+fn build_schedulers(algo: u32, jobs: Vec<Job>, cpus: usize) -> Vec<Box<dyn Scheduler>> {
     match algo {
-        1 => {
-            let mut scheduler = FcfsScheduler::new(jobs);
-            scheduler.run();
-            scheduler.print();
+        1 => vec![Box::new(FcfsScheduler::new(jobs))],
+        2 => vec![Box::new(SjfScheduler::new(jobs))],
+        3 => vec![Box::new(RrScheduler::new(jobs))],
+        5 => vec![Box::new(SrtfScheduler::new(jobs))],
+        6 => {
+            println!("Preemptive? (y/N): ");
+            let mut preemptive = String::new();
+            stdin().read_line(&mut preemptive).unwrap();
+            let mode = match preemptive.trim() {
+                "y" | "Y" => PriorityMode::Preemptive,
+                _ => PriorityMode::NonPreemptive,
+            };
+
+            println!("Break ties by: 1. FCFS 2. Name (default 1): ");
+            let mut tie = String::new();
+            stdin().read_line(&mut tie).unwrap();
+            let tie_break = match tie.trim() {
+                "2" => TieBreak::Name,
+                _ => TieBreak::Fcfs,
+            };
+
+            vec![Box::new(
+                PriorityScheduler::new(jobs).mode(mode).tie_break(tie_break),
+            )]
         }
-        2 => {
-            let mut scheduler = SjfScheduler::new(jobs);
-            scheduler.run();
-            scheduler.print();
+        7 => vec![Box::new(HrrnScheduler::new(jobs))],
+        8 => {
+            let quantums = read_values::<u32>(
+                "Enter the quantum of every level, highest first: (e.g. 1 2 4)",
+                |x| !x.is_empty() && !x.contains(&0),
+            );
+            let boost =
+                read_values::<u32>("Enter the boost interval: (Empty means no boost)", |x| {
+                    x.len() <= 1 && !x.contains(&0)
+                });
+
+            println!("Last level policy: 1. RR 2. FCFS (default 1): ");
+            let mut last_level = String::new();
+            stdin().read_line(&mut last_level).unwrap();
+            let last_level = match last_level.trim() {
+                "2" => LastLevelPolicy::Fcfs,
+                _ => LastLevelPolicy::Rr,
+            };
+
+            let mut scheduler = MlfqScheduler::new(jobs)
+                .levels(quantums)
+                .last_level(last_level);
+            if let [interval] = boost[..] {
+                scheduler = scheduler.boost(interval);
+            }
+
+            vec![Box::new(scheduler)]
         }
-        3 => {
-            println!("Enter rr slice num: ");
-            let mut num = String::new();
-            stdin().read_line(&mut num).unwrap();
-            let slice_num: u32 = match num.trim().parse::<u32>() {
-                Ok(num) => num,
-                Err(..) => panic!("Invalid input. Please try again."),
+        9 => {
+            let mut scheduler = MlqScheduler::new(jobs);
+            for class in JobClass::ALL {
+                scheduler = scheduler.policy(class, read_queue_policy(&class.to_string()));
+            }
+
+            println!("Inter-queue policy: 1. Strict priority 2. Time sliced (default 1): ");
+            let mut inter_queue = String::new();
+            stdin().read_line(&mut inter_queue).unwrap();
+
+            if inter_queue.trim() == "2" {
+                // the shares add up to 100 and every class gets a tick per cycle
+                let numbers = read_values::<u32>(
+                    "Enter the cycle length and the percentage of every class: (e.g. 10 50 30 20)",
+                    |x| match x {
+                        [cycle, shares @ ..] if shares.len() == 3 => {
+                            shares.iter().sum::<u32>() == 100
+                                && shares
+                                    .iter()
+                                    .all(|share| cycle.saturating_mul(*share) / 100 > 0)
+                        }
+                        _ => false,
+                    },
+                );
+
+                scheduler = scheduler.inter_queue(InterQueuePolicy::TimeSliced {
+                    cycle: numbers[0],
+                    shares: [numbers[1], numbers[2], numbers[3]],
+                });
+            }
+
+            vec![Box::new(scheduler)]
+        }
+        10 => vec![Box::new(LotteryScheduler::new(jobs))],
+        11 => vec![Box::new(StrideScheduler::new(jobs))],
+        12 => {
+            let numbers = read_values::<u32>(
+                "Enter the target latency and the minimum granularity: (Empty means 6 1)",
+                |x| (x.is_empty() || x.len() == 2) && !x.contains(&0),
+            );
+
+            let mut scheduler = CfsScheduler::new(jobs);
+            if let [latency, min_granularity] = numbers[..] {
+                scheduler = scheduler.latency(latency).min_granularity(min_granularity);
+            }
+
+            vec![Box::new(scheduler)]
+        }
+        14 => {
+            println!("Ready queues: 1. Global 2. Per CPU 3. Per CPU, periodic balancing 4. Per CPU, work stealing (default 1): ");
+            let mut mode = String::new();
+            stdin().read_line(&mut mode).unwrap();
+            let mode = match mode.trim() {
+                "2" => QueueMode::PerCpu(Balancing::None),
+                "3" => {
+                    let interval = read_values::<u32>("Enter the balancing interval: ", |x| {
+                        x.len() == 1 && x[0] > 0
+                    });
+                    QueueMode::PerCpu(Balancing::Periodic(interval[0]))
+                }
+                "4" => QueueMode::PerCpu(Balancing::WorkStealing),
+                _ => QueueMode::Global,
             };
 
-            let mut scheduler = RrScheduler::new(jobs);
-            scheduler.run(slice_num);
-            scheduler.print();
+            vec![Box::new(
+                SmpScheduler::new(jobs)
+                    .cpus(cpus)
+                    .mode(mode)
+                    .policy(read_queue_policy("ready")),
+            )]
+        }
+        _ => vec![],
+    }
+}
+
+fn read_quanta(default: u32) -> Vec<u32> {
+    println!(
+        "Enter the RR quanta to sweep: (e.g. 1-10 or 2 4 8, Empty means {})",
+        default
+    );
+    let mut quanta = String::new();
+    stdin().read_line(&mut quanta).unwrap();
+
+    let quanta = match quanta.trim().split_once('-') {
+        Some((from, to)) => match (from.trim().parse::<u32>(), to.trim().parse::<u32>()) {
+            (Ok(from), Ok(to)) => Ok((from..=to).collect()),
+            _ => Err(()),
+        },
+        None => quanta
+            .split_whitespace()
+            .map(|x| x.parse::<u32>().map_err(|_| ()))
+            .collect::<Result<Vec<u32>, ()>>(),
+    };
+
+    match quanta {
+        Ok(quanta) if quanta.is_empty() => vec![default],
+        Ok(quanta) if !quanta.contains(&0) => quanta,
+        _ => {
+            println!("Invalid input. Please try again.");
+            read_quanta(default)
         }
-        4 => {
-            let mut fcfs_scheduler = FcfsScheduler::new(jobs.clone());
-            fcfs_scheduler.run();
-            fcfs_scheduler.print();
+    }
+}
 
-            let mut sjf_scheduler = SjfScheduler::new(jobs.clone());
-            sjf_scheduler.run();
-            sjf_scheduler.print();
+/// Runs the selected policies on the same jobs and prints one table of their averages.
+fn compare() {
+    println!("Enter the algorithms to compare: (e.g. 1 2 3 5, Empty means 1 2 3 5 7)");
+    let mut algos = String::new();
+    stdin().read_line(&mut algos).unwrap();
+    let algos = match algos.trim() {
+        "" => Ok(vec![1, 2, 3, 5, 7]),
+        algos => algos
+            .split_whitespace()
+            .map(|x| x.parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>(),
+    };
+
+    // the comparison itself and the periodic tasks can't be compared
+    let algos = match algos {
+        Ok(algos)
+            if algos
+                .iter()
+                .all(|x| (1..=14).contains(x) && *x != 4 && *x != 13) =>
+        {
+            algos
+        }
+        _ => {
+            println!("Invalid input. Please try again.");
+            return compare();
         }
-        _ => {}
     };
 
-    println!("\n{}", "Press any key to continue.");
-    let mut padding = String::new();
-    stdin().read_line(&mut padding).unwrap();
-    main();
+    // the jobs may only run on the CPUs of SMP, if it is compared
+    let cpus = match algos.contains(&14) {
+        true => read_cpus(),
+        false => 1,
+    };
+    let mut comparison = Comparison::new(read_data(cpus));
+    let config = read_config();
+
+    for algo in algos {
+        comparison = match algo {
+            3 => comparison.sweep_quantum(
+                |jobs| Box::new(RrScheduler::new(jobs)),
+                read_quanta(config.quantum),
+                &config,
+            ),
+            _ => comparison.policy(
+                |jobs| build_schedulers(algo, jobs, cpus).pop().unwrap(),
+                &config,
+            ),
+        };
+    }
+
+    print!("{}", comparison);
+}
+
+/// Prints the schedule with its Gantt chart, the chart can be saved as SVG or HTML
+/// and the schedule as a Chrome trace.
+fn show(schedule: Schedule) {
+    schedule.print();
+
+    let gantt = Gantt::new(&schedule);
+    print!("{}", gantt.terminal());
+
+    println!("Save the Gantt chart or trace to: (e.g. rr.svg, rr.html or rr.json, Empty means not saving)");
+    let mut file_path = String::new();
+    stdin().read_line(&mut file_path).unwrap();
+
+    let file_path = file_path.trim();
+    if file_path.is_empty() {
+        return;
+    }
+
+    let chart = match file_path {
+        path if path.ends_with(".html") => gantt.html(),
+        path if path.ends_with(".json") => report::trace(&[schedule]),
+        _ => gantt.svg(),
+    };
+
+    match fs::write(file_path, chart) {
+        Ok(..) => println!("Saved to {}", file_path),
+        Err(why) => println!("couldn't write {}: {}", file_path, why),
+    }
+}
+
+/// The menu, it asks for the jobs and a policy until the process is killed.
+fn interactive() {
+    loop {
+        clr();
+        println!("Welcome to Operating System Algorithms written in Rust.\n");
+
+        let algo = select_algo();
+
+        // periodic tasks are not jobs, they are read instead of the jobs
+        if algo == 13 {
+            println!("Real-time policy: 1. EDF 2. RM (default 1): ");
+            let mut policy = String::new();
+            stdin().read_line(&mut policy).unwrap();
+            let policy = match policy.trim() {
+                "2" => RealtimePolicy::RateMonotonic,
+                _ => RealtimePolicy::Edf,
+            };
+
+            // the loader already checked the timing and the hyperperiod
+            match RealtimeScheduler::new(read_periodic_tasks(), policy) {
+                Ok(mut scheduler) => show(scheduler.run(&SchedulerConfig::default())),
+                Err(why) => println!("{}", why),
+            }
+        } else if algo == 4 {
+            compare();
+        } else {
+            // the other policies run on CPU0 only
+            let cpus = match algo {
+                14 => read_cpus(),
+                _ => 1,
+            };
+            let mut schedulers = build_schedulers(algo, read_data(cpus), cpus);
+            let config = read_config();
+
+            for scheduler in schedulers.iter_mut() {
+                show(scheduler.run(&config));
+            }
+        }
+
+        println!("\nPress any key to continue.");
+        let mut padding = String::new();
+        stdin().read_line(&mut padding).unwrap();
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match cli.command {
+        None | Some(Command::Interactive) => {
+            interactive();
+            ExitCode::SUCCESS
+        }
+        Some(command) => cli::execute(&command),
+    }
 }
@@ -0,0 +1,230 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{JobMetrics, Schedule};
+
+/// Average, maximum and percentiles of one metric over the finished jobs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Summary {
+    pub mean: f32,
+    pub max: f32,
+    pub p50: f32,
+    pub p90: f32,
+    pub p99: f32,
+}
+
+impl Summary {
+    pub fn of(values: &[f32]) -> Summary {
+        if values.is_empty() {
+            return Summary::default();
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        Summary {
+            mean: sorted.iter().sum::<f32>() / sorted.len() as f32,
+            max: sorted[sorted.len() - 1],
+            p50: percentile(&sorted, 50.0),
+            p90: percentile(&sorted, 90.0),
+            p99: percentile(&sorted, 99.0),
+        }
+    }
+}
+
+/// Nearest rank percentile of `sorted`, the smallest value with at least `p`
+/// percent of the values at or below it.
+pub fn percentile(sorted: &[f32], p: f32) -> f32 {
+    let rank = (p / 100.0 * sorted.len() as f32).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// `(sum x)^2 / (n * sum x^2)`, 1 when every value is equal and `1 / n` when one
+/// value takes everything.
+pub fn jain_fairness(values: &[f32]) -> f32 {
+    let sum = values.iter().sum::<f32>();
+    let squares = values.iter().map(|x| x * x).sum::<f32>();
+
+    match squares == 0.0 {
+        true => 1.0,
+        false => sum * sum / (values.len() as f32 * squares),
+    }
+}
+
+/// Metrics of a whole schedule, computed from its segments and finished jobs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Metrics {
+    pub whole_time: Summary,
+    pub weight_whole_time: Summary,
+    pub waiting_time: Summary,
+    pub response_time: Summary,
+    /// Ticks from 0 until the last job finished.
+    pub makespan: u32,
+    pub cpus: usize,
+    /// Ticks the CPUs ran a job, summed over the CPUs.
+    pub busy: u32,
+    /// Ticks the CPUs spent on context switches, summed over the CPUs.
+    pub overhead: u32,
+    /// Idle periods before the makespan, see `Schedule::idle_gaps`.
+    pub idle_gaps: usize,
+    /// Finished jobs per tick.
+    pub throughput: f32,
+    /// Busy and overhead ticks over the ticks every CPU had until the makespan, idle
+    /// gaps included.
+    pub utilization: f32,
+    /// Like `utilization` without the overhead, the share spent on the jobs.
+    pub effective_utilization: f32,
+    /// Jain's index of the service every job got over its turnaround.
+    pub fairness: f32,
+}
+
+impl Metrics {
+    pub fn new(schedule: &Schedule) -> Metrics {
+        let summary = |metric: fn(&JobMetrics) -> f32| {
+            Summary::of(&schedule.jobs.iter().map(metric).collect::<Vec<f32>>())
+        };
+
+        let makespan = schedule.makespan();
+        let cpus = schedule.cpus();
+        let ticks = |overhead: bool| {
+            schedule
+                .segments
+                .iter()
+                .filter(|segment| segment.overhead == overhead)
+                .map(|segment| segment.ticks())
+                .sum::<u32>()
+        };
+        let (busy, overhead) = (ticks(false), ticks(true));
+        let capacity = makespan as f32 * cpus as f32;
+        let progress = schedule
+            .jobs
+            .iter()
+            .map(|job| job.service_time as f32 / job.whole_time as f32)
+            .collect::<Vec<f32>>();
+
+        Metrics {
+            whole_time: summary(|job| job.whole_time as f32),
+            weight_whole_time: summary(|job| job.weight_whole_time),
+            waiting_time: summary(|job| job.waiting_time as f32),
+            response_time: summary(|job| job.response_time as f32),
+            makespan,
+            cpus,
+            busy,
+            overhead,
+            idle_gaps: schedule.idle_gaps().len(),
+            throughput: match makespan {
+                0 => 0.0,
+                makespan => schedule.jobs.len() as f32 / makespan as f32,
+            },
+            utilization: match makespan {
+                0 => 0.0,
+                _ => (busy + overhead) as f32 / capacity,
+            },
+            effective_utilization: match makespan {
+                0 => 0.0,
+                _ => busy as f32 / capacity,
+            },
+            fairness: jain_fairness(&progress),
+        }
+    }
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "\n| {0: <8} | {1: <8} | {2: <8} | {3: <8} | {4: <8} | {5: <8} |",
+            "指标", "平均", "最大", "P50", "P90", "P99"
+        )?;
+
+        for (name, summary) in [
+            ("周转时间", self.whole_time),
+            ("带权周转", self.weight_whole_time),
+            ("等待时间", self.waiting_time),
+            ("响应时间", self.response_time),
+        ] {
+            writeln!(
+                f,
+                "| {0: <8} | {1: <10.2} | {2: <10.2} | {3: <10.2} | {4: <10.2} | {5: <10.2} |",
+                name, summary.mean, summary.max, summary.p50, summary.p90, summary.p99
+            )?;
+        }
+
+        writeln!(
+            f,
+            "完成时间: {}, 吞吐量: {:.3} 个/tick, CPU 利用率: {:.1}% ({} 个 CPU, {} 段空闲), Jain 公平指数: {:.3}",
+            self.makespan,
+            self.throughput,
+            self.utilization * 100.0,
+            self.cpus,
+            self.idle_gaps,
+            self.fairness
+        )?;
+
+        if self.overhead > 0 {
+            writeln!(
+                f,
+                "上下文切换开销: {} ticks, 有效利用率: {:.1}%",
+                self.overhead,
+                self.effective_utilization * 100.0
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        build_jobs,
+        metrics::{jain_fairness, percentile},
+        rr::RrScheduler,
+        srtf::SrtfScheduler,
+        Job, Scheduler, SchedulerConfig,
+    };
+
+    #[test]
+    fn test() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        assert_eq!(percentile(&sorted, 50.0), 5.0);
+        assert_eq!(percentile(&sorted, 90.0), 9.0);
+        assert_eq!(percentile(&sorted, 99.0), 10.0);
+        assert_eq!(jain_fairness(&[2.0, 2.0, 2.0]), 1.0);
+        assert_eq!(jain_fairness(&[1.0, 0.0, 0.0, 0.0]), 0.25);
+
+        let schedule = RrScheduler::new(build_jobs()).run(&SchedulerConfig::default().quantum(2));
+        schedule.print();
+
+        // A runs at once, B waits for the first slice of A
+        let (a, b) = (schedule.job("A").unwrap(), schedule.job("B").unwrap());
+        assert_eq!((a.response_time, b.response_time), (0, 1));
+        assert_eq!(a.waiting_time, a.whole_time - a.service_time);
+
+        let metrics = schedule.metrics();
+        assert_eq!(
+            (metrics.makespan, metrics.busy, metrics.idle_gaps),
+            (25, 25, 0)
+        );
+        assert_eq!(metrics.utilization, 1.0);
+        assert_eq!(metrics.waiting_time.max, 15.0);
+    }
+
+    #[test]
+    fn idle_gaps() {
+        let jobs: Vec<Job> = vec![Job::new("A", 2, 3), Job::new("B", 8, 2)];
+        let schedule = SrtfScheduler::new(jobs).run(&SchedulerConfig::default());
+
+        let gaps = schedule
+            .idle_gaps()
+            .iter()
+            .map(|gap| (gap.start, gap.end))
+            .collect::<Vec<(u32, u32)>>();
+        assert_eq!(gaps, vec![(0, 2), (5, 8)]);
+
+        let metrics = schedule.metrics();
+        assert_eq!(metrics.utilization, 0.5);
+        assert_eq!(metrics.throughput, 0.2);
+    }
+}
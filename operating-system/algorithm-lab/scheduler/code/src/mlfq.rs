@@ -0,0 +1,292 @@
+use std::collections::VecDeque;
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+/// Policy used by the lowest queue, the one jobs can not be demoted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LastLevelPolicy {
+    Fcfs,
+    Rr,
+}
+
+pub struct MlfqScheduler {
+    jobs: Vec<Job>,
+    quantums: Vec<u32>,
+    boost_interval: Option<u32>,
+    last_level: LastLevelPolicy,
+}
+
+impl MlfqScheduler {
+    /// One quantum per level, level 0 is the highest.
+    pub fn levels(mut self, quantums: Vec<u32>) -> MlfqScheduler {
+        assert!(!quantums.is_empty(), "MLFQ needs at least one level");
+        assert!(quantums.iter().all(|&q| q > 0), "quantum must be positive");
+
+        self.quantums = quantums;
+        self
+    }
+
+    /// Moves every job back to the highest level every `interval` ticks.
+    pub fn boost(mut self, interval: u32) -> MlfqScheduler {
+        assert!(interval > 0, "boost interval must be positive");
+
+        self.boost_interval = Some(interval);
+        self
+    }
+
+    pub fn last_level(mut self, policy: LastLevelPolicy) -> MlfqScheduler {
+        self.last_level = policy;
+        self
+    }
+}
+
+/// One queue per level, a job is demoted a level whenever it uses up its quantum.
+#[derive(Debug, Clone)]
+struct MlfqQueues {
+    quantums: Vec<u32>,
+    boost_interval: Option<u32>,
+    last_level: LastLevelPolicy,
+    queues: Vec<VecDeque<usize>>,
+    levels: Vec<usize>,
+    // ticks used of the current quantum, kept when a job is preempted
+    used: Vec<u32>,
+    /// The job a boost took off the CPU, it is already back in a queue.
+    boosted: Option<usize>,
+}
+
+impl MlfqQueues {
+    fn new(scheduler: &MlfqScheduler) -> MlfqQueues {
+        let task_len = scheduler.jobs.len();
+
+        MlfqQueues {
+            quantums: scheduler.quantums.clone(),
+            boost_interval: scheduler.boost_interval,
+            last_level: scheduler.last_level,
+            queues: vec![VecDeque::new(); scheduler.quantums.len()],
+            levels: vec![0; task_len],
+            used: vec![0; task_len],
+            boosted: None,
+        }
+    }
+
+    /// Levels of the jobs in the system at `time`.
+    fn levels_detail(&self, ctx: &Context, time: u32) -> String {
+        if !ctx.logging() {
+            return String::new();
+        }
+
+        let levels = ctx
+            .jobs
+            .iter()
+            .zip(&self.levels)
+            .filter(|(job, _)| job.arrival_time <= time && job.finish_time.is_none())
+            .map(|(job, level)| format!("{}=L{}", job.name, level))
+            .collect::<Vec<String>>();
+
+        format!("LEVELS: {}", levels.join(" "))
+    }
+}
+
+impl Policy for MlfqQueues {
+    // ENQUEUE: new jobs enter the highest level, jobs back from I/O their own
+    fn enqueue(&mut self, job: usize, _ctx: &mut Context) {
+        self.queues[self.levels[job]].push_back(job);
+    }
+
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        let level = self.queues.iter().position(|queue| !queue.is_empty())?;
+        let job = self.queues[level].pop_front().unwrap();
+        let pick = Pick::new(job).detail(self.levels_detail(ctx, ctx.now));
+
+        let last_level = self.quantums.len() - 1;
+        match level == last_level && self.last_level == LastLevelPolicy::Fcfs {
+            true => Some(pick),
+            false => Some(pick.slice(self.quantums[level] - self.used[job])),
+        }
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        self.queues.iter().flatten().copied().collect()
+    }
+
+    fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    // TICK: the levels stay the same while a job runs
+    fn ran(&mut self, job: usize, ticks: u32, ctx: &mut Context) {
+        self.used[job] += ticks;
+
+        if ctx.logging() {
+            let queue = self.queue(ctx.cpu);
+            for time in ctx.now - ticks..ctx.now {
+                let detail = self.levels_detail(ctx, time);
+                ctx.log_tick(time, job, &queue, detail);
+            }
+        }
+    }
+
+    // DEMOTE: the job has used its whole quantum
+    fn expired(&mut self, job: usize, ctx: &mut Context) {
+        let level = self.levels[job];
+        let next_level = (level + 1).min(self.quantums.len() - 1);
+        self.levels[job] = next_level;
+        self.used[job] = 0;
+        self.queues[next_level].push_back(job);
+
+        // the last level is plain RR, the job just goes to the tail
+        if next_level != level {
+            let detail = format!("to L{}", next_level);
+            ctx.log("DEMOTED", Some(job), &[], Some(detail));
+        }
+    }
+
+    // PREEMPT: a boost, or a job is waiting in a higher level than the running one
+    fn preempts(&mut self, job: usize, ctx: &mut Context) -> Option<String> {
+        let higher = self.queues[..self.levels[job]]
+            .iter()
+            .any(|queue| !queue.is_empty());
+
+        match self.boosted == Some(job) || higher {
+            true => Some(self.levels_detail(ctx, ctx.now)),
+            false => None,
+        }
+    }
+
+    fn preempted(&mut self, job: usize, _ctx: &mut Context) {
+        if self.boosted.take() != Some(job) {
+            self.queues[self.levels[job]].push_front(job);
+        }
+    }
+
+    fn done(&mut self, job: usize, _ctx: &mut Context) {
+        self.used[job] = 0;
+    }
+
+    fn next_timer(&self, ctx: &Context) -> Option<u32> {
+        let interval = self.boost_interval?;
+        let busy = ctx.running(0).is_some() || !Policy::is_empty(self);
+
+        busy.then_some((ctx.now / interval + 1) * interval)
+    }
+
+    // BOOST: everybody goes back to the highest level, blocked and held jobs too
+    fn timer(&mut self, ctx: &mut Context) {
+        if let Some(idx) = ctx.running(0) {
+            self.queues[self.levels[idx]].push_front(idx);
+            self.boosted = Some(idx);
+        }
+
+        for level in 1..self.queues.len() {
+            while let Some(idx) = self.queues[level].pop_front() {
+                self.queues[0].push_back(idx);
+            }
+        }
+
+        for (idx, job) in ctx.jobs.iter().enumerate() {
+            if job.finish_time.is_none() {
+                self.levels[idx] = 0;
+                self.used[idx] = 0;
+            }
+        }
+
+        let detail = self.levels_detail(ctx, ctx.now);
+        ctx.log("BOOST", None, &[], Some(detail));
+    }
+}
+
+impl Scheduler for MlfqScheduler {
+    fn new(mut jobs: Vec<Job>) -> MlfqScheduler {
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        MlfqScheduler {
+            jobs,
+            quantums: vec![1, 2, 4],
+            boost_interval: None,
+            last_level: LastLevelPolicy::Rr,
+        }
+    }
+
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("MLFQ（多级反馈队列）");
+
+        let mut queues = MlfqQueues::new(self);
+        Engine::new(&mut self.jobs, config).run(&mut queues, &mut schedule);
+
+        schedule.note(format!("quantums: {:?}", self.quantums));
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        build_jobs,
+        mlfq::{LastLevelPolicy, MlfqScheduler},
+        Burst, Job, Scheduler, SchedulerConfig,
+    };
+
+    #[test]
+    fn test() {
+        let jobs: Vec<Job> = build_jobs();
+
+        let mut scheduler = MlfqScheduler::new(jobs.clone()).levels(vec![1, 2, 4]);
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+
+        let finish_times = scheduler
+            .jobs
+            .iter()
+            .map(|job| job.finish_time.unwrap())
+            .collect::<Vec<u32>>();
+        assert_eq!(finish_times, vec![16, 20, 11, 25, 14]);
+        // one TICK with the levels for every tick a job ran
+        let ticks = schedule
+            .events
+            .iter()
+            .filter(|process| process.status == "TICK")
+            .count() as u32;
+        assert_eq!(ticks, jobs.iter().map(|job| job.service_time).sum::<u32>());
+        // D uses up its L1 quantum at 13
+        assert!(schedule
+            .events
+            .iter()
+            .any(|process| process.status == "DEMOTED"
+                && process.job.as_ref().unwrap().name == "D"
+                && process.current_time == 13
+                && process.detail.as_deref() == Some("to L2")));
+
+        let mut scheduler = MlfqScheduler::new(jobs)
+            .levels(vec![2, 4])
+            .boost(10)
+            .last_level(LastLevelPolicy::Fcfs);
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+
+        assert!(scheduler.jobs.iter().all(|job| job.served_time == job.service_time));
+        assert!(schedule
+            .events
+            .iter()
+            .any(|process| process.status == "BOOST" && process.current_time == 10));
+
+        // A is blocked on I/O over the boost at 4, so it comes back to L0 and
+        // preempts B in L1
+        let jobs = vec![
+            Job::with_bursts("A", 0, vec![Burst::Cpu(3), Burst::Io(6), Burst::Cpu(2)]),
+            Job::new("B", 0, 20),
+        ];
+        let mut scheduler = MlfqScheduler::new(jobs).levels(vec![1, 10]).boost(4);
+        let schedule = scheduler.run(&SchedulerConfig::default().event_log(true));
+        schedule.print();
+
+        assert!(schedule
+            .events
+            .iter()
+            .any(|process| process.status == "RUNNING"
+                && process.job.as_ref().unwrap().name == "A"
+                && process.current_time == 10));
+    }
+}
@@ -0,0 +1,376 @@
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    fcfs::FcfsQueue,
+    rr::RrQueue,
+    sjf::SjfQueue,
+    Job, JobClass, Schedule, Scheduler, SchedulerConfig,
+};
+
+/// Policy used inside the queue of one class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueuePolicy {
+    Fcfs,
+    Sjf,
+    /// Round robin with the given slice size.
+    Rr(u32),
+}
+
+impl QueuePolicy {
+    /// An empty queue ordered by this policy.
+    pub fn build(&self) -> Box<dyn Policy> {
+        match self {
+            QueuePolicy::Fcfs => Box::new(FcfsQueue::default()),
+            QueuePolicy::Sjf => Box::new(SjfQueue::default()),
+            QueuePolicy::Rr(slice_size) => Box::new(RrQueue::new(*slice_size)),
+        }
+    }
+}
+
+/// How the CPU is divided between the class queues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterQueuePolicy {
+    /// A lower queue only runs while every higher queue is empty.
+    StrictPriority,
+    /// Every `cycle` ticks each class owns its percentage of the CPU, system first.
+    /// Ticks a queue leaves unused are passed down to the queues below it.
+    TimeSliced { cycle: u32, shares: [u32; 3] },
+}
+
+impl InterQueuePolicy {
+    /// The class owning tick `time`, it and the classes below it may use the tick.
+    fn owner(&self, time: u32) -> usize {
+        match self {
+            InterQueuePolicy::StrictPriority => 0,
+            InterQueuePolicy::TimeSliced { cycle, shares } => {
+                let offset = time % cycle;
+                let mut bound = 0;
+
+                shares
+                    .iter()
+                    .position(|share| {
+                        bound += share;
+                        offset < share_ticks(*cycle, bound)
+                    })
+                    .unwrap()
+            }
+        }
+    }
+
+    /// The first tick after `time` where the owner may change.
+    fn next_window(&self, time: u32) -> Option<u32> {
+        match self {
+            InterQueuePolicy::StrictPriority => None,
+            InterQueuePolicy::TimeSliced { cycle, shares } => {
+                let start = time - time % cycle;
+                let mut bound = 0;
+
+                shares
+                    .iter()
+                    .map(|share| {
+                        bound += share;
+                        start.saturating_add(share_ticks(*cycle, bound))
+                    })
+                    .find(|&end| end > time)
+            }
+        }
+    }
+}
+
+/// The ticks of `cycle` that `percent` of it, at most 100, makes up.
+fn share_ticks(cycle: u32, percent: u32) -> u32 {
+    (cycle as u64 * percent as u64 / 100) as u32
+}
+
+/// Every class queue is scheduled by the FCFS, SJF or RR queue of its policy,
+/// running on the ticks left to it by the inter-queue policy.
+pub struct MlqScheduler {
+    jobs: Vec<Job>,
+    policies: [QueuePolicy; 3],
+    inter_queue: InterQueuePolicy,
+}
+
+/// One queue per class. A job taken off at the end of a window goes on first when
+/// its class runs again, one whose slice ran out only goes to the back of its queue
+/// then, behind whatever arrived in between.
+struct MlqQueues {
+    inter_queue: InterQueuePolicy,
+    queues: Vec<Box<dyn Policy>>,
+    /// Ran out of its slice and waits for its class to run again.
+    expired: [Option<usize>; 3],
+    /// Ticks the running job may run before its class queue wants it back.
+    slice: Option<u32>,
+    ticks: u32,
+}
+
+impl MlqQueues {
+    fn is_empty(&self, class: usize) -> bool {
+        self.queues[class].is_empty() && self.expired[class].is_none()
+    }
+
+    /// The highest non-empty class allowed to run at `time`.
+    fn runnable(&self, time: u32) -> Option<usize> {
+        (self.inter_queue.owner(time)..JobClass::ALL.len()).find(|&class| !self.is_empty(class))
+    }
+}
+
+impl Policy for MlqQueues {
+    fn enqueue(&mut self, job: usize, ctx: &mut Context) {
+        let class = ctx.jobs[job].class.index();
+        self.queues[class].enqueue(job, ctx);
+    }
+
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        let class = self.runnable(ctx.now)?;
+        if let Some(job) = self.expired[class].take() {
+            self.queues[class].expired(job, ctx);
+        }
+
+        let pick = self.queues[class].pick(ctx)?;
+        self.slice = pick.slice;
+        self.ticks = 0;
+
+        // the window of the class may end before the slice does
+        let slice = match self.inter_queue.next_window(ctx.now) {
+            Some(end) => pick.slice.unwrap_or(u32::MAX).min(end - ctx.now),
+            None => pick.slice.unwrap_or(u32::MAX),
+        };
+        let pick = Pick::new(pick.job).detail(format!("{} queue", JobClass::ALL[class]));
+
+        match slice {
+            u32::MAX => Some(pick),
+            slice => Some(pick.slice(slice)),
+        }
+    }
+
+    fn queue(&self, cpu: usize) -> Vec<usize> {
+        (0..JobClass::ALL.len())
+            .flat_map(|class| {
+                let mut queue = self.queues[class].queue(cpu);
+                queue.extend(self.expired[class]);
+                queue
+            })
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        let expired = self.expired.iter().flatten().count();
+
+        self.queues.iter().map(|queue| queue.len()).sum::<usize>() + expired
+    }
+
+    fn ran(&mut self, job: usize, ticks: u32, ctx: &mut Context) {
+        self.ticks += ticks;
+        self.queues[ctx.jobs[job].class.index()].ran(job, ticks, ctx);
+    }
+
+    fn expired(&mut self, job: usize, ctx: &mut Context) {
+        let class = ctx.jobs[job].class.index();
+
+        match self.slice {
+            Some(slice) if self.ticks >= slice => self.expired[class] = Some(job),
+            // the window is over, not the slice
+            _ => self.queues[class].preempted(job, ctx),
+        }
+    }
+
+    // PREEMPTED: a higher queue allowed to run has a job
+    fn preempts(&mut self, job: usize, ctx: &mut Context) -> Option<String> {
+        let class = ctx.jobs[job].class.index();
+
+        self.runnable(ctx.now)
+            .filter(|&higher| higher < class)
+            .map(|higher| format!("{} queue", JobClass::ALL[higher]))
+    }
+
+    fn preempted(&mut self, job: usize, ctx: &mut Context) {
+        let class = ctx.jobs[job].class.index();
+        self.queues[class].preempted(job, ctx);
+    }
+
+    fn done(&mut self, job: usize, ctx: &mut Context) {
+        let class = ctx.jobs[job].class.index();
+        self.queues[class].done(job, ctx);
+    }
+
+    /// Wakes the idle CPU when a window lets a waiting class run.
+    fn next_timer(&self, ctx: &Context) -> Option<u32> {
+        match ctx.running(0) {
+            None if Policy::len(self) > 0 => self.inter_queue.next_window(ctx.now),
+            _ => None,
+        }
+    }
+}
+
+impl MlqScheduler {
+    pub fn policy(mut self, class: JobClass, policy: QueuePolicy) -> MlqScheduler {
+        if let QueuePolicy::Rr(slice_size) = policy {
+            assert!(slice_size > 0, "slice size must be positive");
+        }
+
+        self.policies[class.index()] = policy;
+        self
+    }
+
+    pub fn inter_queue(mut self, inter_queue: InterQueuePolicy) -> MlqScheduler {
+        if let InterQueuePolicy::TimeSliced { cycle, shares } = inter_queue {
+            assert_eq!(shares.iter().sum::<u32>(), 100, "shares must add up to 100");
+            assert!(
+                shares
+                    .iter()
+                    .all(|&share| cycle.saturating_mul(share) / 100 > 0),
+                "every class needs at least one tick per cycle"
+            );
+        }
+
+        self.inter_queue = inter_queue;
+        self
+    }
+}
+
+impl Scheduler for MlqScheduler {
+    fn new(mut jobs: Vec<Job>) -> MlqScheduler {
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        MlqScheduler {
+            jobs,
+            policies: [QueuePolicy::Fcfs, QueuePolicy::Rr(2), QueuePolicy::Sjf],
+            inter_queue: InterQueuePolicy::StrictPriority,
+        }
+    }
+
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("MLQ（多级队列）");
+
+        let mut queues = MlqQueues {
+            inter_queue: self.inter_queue,
+            queues: self.policies.iter().map(QueuePolicy::build).collect(),
+            expired: [None; 3],
+            slice: None,
+            ticks: 0,
+        };
+        Engine::new(&mut self.jobs, config).run(&mut queues, &mut schedule);
+
+        for class in JobClass::ALL {
+            let whole_times = self
+                .jobs
+                .iter()
+                .filter(|job| job.class == class)
+                .filter_map(|job| job.whole_time)
+                .collect::<Vec<u32>>();
+
+            if whole_times.is_empty() {
+                continue;
+            }
+
+            schedule.note(format!(
+                "{} ({:?}) 平均周转时间: {}",
+                class,
+                self.policies[class.index()],
+                whole_times.iter().sum::<u32>() as f32 / whole_times.len() as f32
+            ));
+        }
+
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        build_jobs,
+        mlq::{InterQueuePolicy, MlqScheduler},
+        Job, JobClass, Scheduler, SchedulerConfig,
+    };
+
+    fn build_class_jobs() -> Vec<Job> {
+        let classes = [
+            JobClass::Batch,
+            JobClass::Interactive,
+            JobClass::System,
+            JobClass::Batch,
+            JobClass::Interactive,
+        ];
+
+        build_jobs()
+            .into_iter()
+            .zip(classes)
+            .map(|(job, class)| Job { class, ..job })
+            .collect()
+    }
+
+    fn finish_times(scheduler: &MlqScheduler) -> Vec<u32> {
+        scheduler
+            .jobs
+            .iter()
+            .map(|job| job.finish_time.unwrap())
+            .collect::<Vec<u32>>()
+    }
+
+    #[test]
+    fn test() {
+        let mut scheduler = MlqScheduler::new(build_class_jobs());
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+        assert_eq!(finish_times(&scheduler), vec![17, 13, 6, 25, 8]);
+
+        let mut scheduler = MlqScheduler::new(build_class_jobs()).inter_queue(
+            InterQueuePolicy::TimeSliced {
+                cycle: 10,
+                shares: [50, 30, 20],
+            },
+        );
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+
+        let served = schedule
+            .events
+            .iter()
+            .filter(|process| process.status == "FINISHED")
+            .count();
+        assert_eq!(served, 5);
+
+        // one long job per class keeps every class busy for the first cycles
+        let jobs = JobClass::ALL
+            .iter()
+            .map(|&class| Job {
+                class,
+                ..Job::new(&class.to_string(), 0, 20)
+            })
+            .collect();
+        let mut scheduler = MlqScheduler::new(jobs).inter_queue(InterQueuePolicy::TimeSliced {
+            cycle: 10,
+            shares: [50, 30, 20],
+        });
+        let schedule = scheduler.run(&SchedulerConfig::default());
+
+        for cycle in 0..3 {
+            let (start, end) = (cycle * 10, cycle * 10 + 10);
+            let ticks = JobClass::ALL.map(|class| {
+                schedule
+                    .segments
+                    .iter()
+                    .filter(|segment| segment.job == Some(class.to_string()))
+                    .map(|segment| {
+                        segment
+                            .end
+                            .min(end)
+                            .saturating_sub(segment.start.max(start))
+                    })
+                    .sum::<u32>()
+            });
+            assert_eq!(ticks, [5, 3, 2], "cycle {}", cycle);
+        }
+
+        // the windows of a cycle this long are computed without overflowing
+        let jobs = vec![Job {
+            class: JobClass::Batch,
+            ..Job::new("A", 0, 20)
+        }];
+        let mut scheduler = MlqScheduler::new(jobs).inter_queue(InterQueuePolicy::TimeSliced {
+            cycle: u32::MAX,
+            shares: [50, 30, 20],
+        });
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        assert_eq!(schedule.finish_time("A"), Some(20));
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    Aging, Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityMode {
+    /// A higher priority arrival takes the CPU from the running job.
+    Preemptive,
+    /// The running job always runs to completion.
+    NonPreemptive,
+}
+
+/// How to choose between ready jobs with the same priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    Fcfs,
+    Name,
+}
+
+pub struct PriorityScheduler {
+    jobs: Vec<Job>,
+    mode: PriorityMode,
+    tie_break: TieBreak,
+}
+
+impl PriorityScheduler {
+    pub fn mode(mut self, mode: PriorityMode) -> PriorityScheduler {
+        self.mode = mode;
+        self
+    }
+
+    pub fn tie_break(mut self, tie_break: TieBreak) -> PriorityScheduler {
+        self.tie_break = tie_break;
+        self
+    }
+}
+
+/// Ready jobs by priority, then by the tie break.
+#[derive(Debug, Clone)]
+struct PriorityQueue {
+    mode: PriorityMode,
+    /// Position of every job under the tie break.
+    rank: Vec<usize>,
+    /// (priority, rank, job)
+    ready: BTreeSet<(u32, usize, usize)>,
+    aging: Option<Aging>,
+    /// Priority the running job was picked with, aged or not.
+    running: u32,
+}
+
+impl PriorityQueue {
+    fn new(
+        jobs: &[Job],
+        mode: PriorityMode,
+        tie_break: TieBreak,
+        aging: Option<Aging>,
+    ) -> PriorityQueue {
+        let mut order = (0..jobs.len()).collect::<Vec<usize>>();
+        if tie_break == TieBreak::Name {
+            order.sort_by(|&a, &b| jobs[a].name.cmp(&jobs[b].name));
+        }
+
+        let mut rank = vec![0; jobs.len()];
+        for (position, idx) in order.into_iter().enumerate() {
+            rank[idx] = position;
+        }
+
+        PriorityQueue {
+            mode,
+            rank,
+            ready: BTreeSet::new(),
+            aging,
+            running: 0,
+        }
+    }
+
+    /// The best waiting entry by its aged priority, every call scans the queue when
+    /// aging is on.
+    fn first(&self, ctx: &Context) -> Option<((u32, usize, usize), u32)> {
+        let Some(aging) = self.aging else {
+            return self.ready.first().map(|&entry| (entry, entry.0));
+        };
+
+        self.ready
+            .iter()
+            .map(|&entry| {
+                let (priority, _, job) = entry;
+                (entry, aging.aged(priority, ctx.now - ctx.ready_since(job)))
+            })
+            .min_by_key(|&((_, rank, job), aged)| (aged, rank, job))
+    }
+}
+
+impl Policy for PriorityQueue {
+    fn enqueue(&mut self, job: usize, ctx: &mut Context) {
+        self.ready
+            .insert((ctx.jobs[job].priority, self.rank[job], job));
+    }
+
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        let (entry, aged) = self.first(ctx)?;
+        self.ready.remove(&entry);
+        self.running = aged;
+
+        let (priority, _, job) = entry;
+        Some(match aged < priority {
+            true => Pick::new(job).detail(format!("aged {} -> {}", priority, aged)),
+            false => Pick::new(job),
+        })
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        self.ready.iter().map(|&(_, _, job)| job).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.ready.len()
+    }
+
+    // the running job keeps the CPU against equal priorities
+    fn preempts(&mut self, _job: usize, ctx: &mut Context) -> Option<String> {
+        if self.mode == PriorityMode::NonPreemptive {
+            return None;
+        }
+
+        self.first(ctx)
+            .filter(|&(_, aged)| aged < self.running)
+            .map(|((_, _, higher), _)| format!("by {}", ctx.jobs[higher].name))
+    }
+
+    // AGING: wakes up when a waiting job ages past the running one
+    fn next_timer(&self, ctx: &Context) -> Option<u32> {
+        let aging = self.aging?;
+        if self.mode == PriorityMode::NonPreemptive {
+            return None;
+        }
+        ctx.running(0)?;
+
+        self.ready
+            .iter()
+            .filter_map(|&(priority, _, job)| {
+                aging
+                    .waiting_to_beat(priority, self.running)
+                    .map(|waiting| ctx.ready_since(job) + waiting)
+            })
+            .filter(|&time| time > ctx.now)
+            .min()
+    }
+}
+
+impl Scheduler for PriorityScheduler {
+    fn new(mut jobs: Vec<Job>) -> PriorityScheduler {
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        PriorityScheduler {
+            jobs,
+            mode: PriorityMode::NonPreemptive,
+            tie_break: TieBreak::Fcfs,
+        }
+    }
+
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new(match self.mode {
+            PriorityMode::Preemptive => "Priority（抢占式优先级）",
+            PriorityMode::NonPreemptive => "Priority（非抢占式优先级）",
+        });
+
+        let mut queue = PriorityQueue::new(&self.jobs, self.mode, self.tie_break, config.aging);
+        Engine::new(&mut self.jobs, config).run(&mut queue, &mut schedule);
+
+        if let Some(aging) = config.aging {
+            schedule.note(aging.to_string());
+        }
+
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        build_jobs,
+        priority::{PriorityMode, PriorityScheduler, TieBreak},
+        Aging, Job, Scheduler, SchedulerConfig,
+    };
+
+    fn finish_times(scheduler: &PriorityScheduler) -> Vec<u32> {
+        scheduler
+            .jobs
+            .iter()
+            .map(|job| job.finish_time.unwrap())
+            .collect::<Vec<u32>>()
+    }
+
+    #[test]
+    fn test() {
+        let jobs: Vec<Job> = build_jobs();
+
+        let mut scheduler = PriorityScheduler::new(jobs.clone());
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+        assert_eq!(finish_times(&scheduler), vec![5, 15, 8, 25, 17]);
+
+        let mut scheduler = PriorityScheduler::new(jobs)
+            .mode(PriorityMode::Preemptive)
+            .tie_break(TieBreak::Name);
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+        assert_eq!(finish_times(&scheduler), vec![17, 11, 6, 25, 13]);
+
+        // L gains a level every 2 ticks and passes the 2 of H after waiting 8
+        let jobs = vec![
+            Job { priority: 2, ..Job::new("H", 0, 10) },
+            Job { priority: 5, ..Job::new("L", 0, 3) },
+        ];
+        let mut scheduler = PriorityScheduler::new(jobs).mode(PriorityMode::Preemptive);
+        let schedule = scheduler.run(&SchedulerConfig::default().aging(Aging::new(2, 10)));
+        schedule.print();
+        assert_eq!(finish_times(&scheduler), vec![13, 11]);
+        assert!(schedule
+            .events
+            .iter()
+            .any(|process| process.status == "PREEMPTED" && process.current_time == 8));
+    }
+}
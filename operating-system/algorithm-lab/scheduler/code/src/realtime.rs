@@ -0,0 +1,397 @@
+use std::{collections::BTreeSet, fmt};
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    Job, Schedule, SchedulerConfig,
+};
+
+/// A task released every `period` ticks from time 0, each instance needs at most
+/// `wcet` ticks and has to finish within `deadline` ticks of its release.
+#[derive(Debug, Clone)]
+pub struct PeriodicTask {
+    pub name: String,
+    pub period: u32,
+    pub wcet: u32,
+    pub deadline: u32,
+}
+
+impl PeriodicTask {
+    /// A task with an implicit deadline, equal to its period.
+    pub fn new(name: &str, period: u32, wcet: u32) -> PeriodicTask {
+        PeriodicTask {
+            name: name.to_string(),
+            period,
+            wcet,
+            deadline: period,
+        }
+    }
+
+    /// Checks `0 < wcet <= deadline <= period`.
+    pub fn check(&self) -> Result<(), TaskError> {
+        match 0 < self.wcet && self.wcet <= self.deadline && self.deadline <= self.period {
+            true => Ok(()),
+            false => Err(TaskError::Timing(self.name.clone())),
+        }
+    }
+}
+
+/// Why periodic tasks can't be simulated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskError {
+    /// The task does not have `0 < wcet <= deadline <= period`.
+    Timing(String),
+    /// The least common multiple of the periods does not fit in a `u32`.
+    Hyperperiod,
+    /// A hyperperiod releases this many instances, more than `MAX_INSTANCES`.
+    Instances(u64),
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TaskError::Timing(name) => write!(f, "{} needs 0 < wcet <= deadline <= period", name),
+            TaskError::Hyperperiod => write!(f, "the hyperperiod is above {}", u32::MAX),
+            TaskError::Instances(instances) => write!(
+                f,
+                "the hyperperiod releases {} instances, more than {}",
+                instances, MAX_INSTANCES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TaskError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RealtimePolicy {
+    /// Earliest absolute deadline first.
+    Edf,
+    /// Fixed priorities, the shorter the period the higher.
+    RateMonotonic,
+}
+
+pub fn utilization(tasks: &[PeriodicTask]) -> f64 {
+    tasks
+        .iter()
+        .map(|task| task.wcet as f64 / task.period as f64)
+        .sum()
+}
+
+/// `n (2^(1/n) - 1)`, rate monotonic always meets its deadlines below it.
+pub fn liu_layland_bound(n: usize) -> f64 {
+    let n = n as f64;
+    n * (2f64.powf(1.0 / n) - 1.0)
+}
+
+/// The schedule repeats itself after the least common multiple of the periods,
+/// `None` if it does not fit in a `u32`.
+pub fn hyperperiod(tasks: &[PeriodicTask]) -> Option<u32> {
+    fn gcd(a: u32, b: u32) -> u32 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    tasks.iter().try_fold(1u32, |lcm, task| {
+        (lcm / gcd(lcm, task.period)).checked_mul(task.period)
+    })
+}
+
+/// Most instances a simulated hyperperiod may release, every one is kept as a job.
+pub const MAX_INSTANCES: u64 = 1_000_000;
+
+/// The hyperperiod of `tasks` if it fits in a `u32` and releases at most
+/// `MAX_INSTANCES` instances.
+pub fn checked_hyperperiod(tasks: &[PeriodicTask]) -> Result<u32, TaskError> {
+    let hyperperiod = hyperperiod(tasks).ok_or(TaskError::Hyperperiod)?;
+    let instances = tasks
+        .iter()
+        .map(|task| (hyperperiod / task.period) as u64)
+        .sum::<u64>();
+
+    match instances <= MAX_INSTANCES {
+        true => Ok(hyperperiod),
+        false => Err(TaskError::Instances(instances)),
+    }
+}
+
+/// Simulates one hyperperiod. An instance that reaches its deadline unfinished
+/// is reported as missed and dropped.
+pub struct RealtimeScheduler {
+    tasks: Vec<PeriodicTask>,
+    policy: RealtimePolicy,
+    hyperperiod: u32,
+    /// Every released instance, `deadline` holds its absolute deadline.
+    instances: Vec<Job>,
+    missed: Vec<bool>,
+}
+
+impl RealtimeScheduler {
+    pub fn new(
+        tasks: Vec<PeriodicTask>,
+        policy: RealtimePolicy,
+    ) -> Result<RealtimeScheduler, TaskError> {
+        for task in &tasks {
+            task.check()?;
+        }
+        let hyperperiod = checked_hyperperiod(&tasks)?;
+
+        Ok(RealtimeScheduler {
+            tasks,
+            policy,
+            hyperperiod,
+            instances: Vec::new(),
+            missed: Vec::new(),
+        })
+    }
+
+    /// Instances that missed their deadline.
+    pub fn missed(&self) -> Vec<&Job> {
+        self.instances
+            .iter()
+            .zip(&self.missed)
+            .filter(|(_, &missed)| missed)
+            .map(|(job, _)| job)
+            .collect()
+    }
+
+    /// Simulates one hyperperiod, the instances run until they finish or a more
+    /// urgent one is released, so `config.quantum` is not used.
+    pub fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new(match self.policy {
+            RealtimePolicy::Edf => "EDF（最早截止时间优先）",
+            RealtimePolicy::RateMonotonic => "RM（单调速率）",
+        });
+
+        let hyperperiod = self.hyperperiod;
+        let mut released = Vec::new();
+
+        // RELEASE: a new instance at the start of every period
+        for (task_idx, task) in self.tasks.iter().enumerate() {
+            for release in (0..hyperperiod).step_by(task.period as usize) {
+                let mut job = Job::new(
+                    &format!("{}#{}", task.name, release / task.period),
+                    release,
+                    task.wcet,
+                );
+                job.deadline = Some(release + task.deadline);
+
+                released.push((job, task_idx));
+            }
+        }
+        released.sort_by_key(|(job, task_idx)| (job.arrival_time, *task_idx));
+
+        let (mut instances, tasks_of): (Vec<Job>, Vec<usize>) = released.into_iter().unzip();
+        let mut queue = RealtimeQueue {
+            keys: instances
+                .iter()
+                .zip(&tasks_of)
+                .map(|(job, &task_idx)| match self.policy {
+                    RealtimePolicy::Edf => (job.deadline.unwrap(), task_idx, job.arrival_time),
+                    RealtimePolicy::RateMonotonic => {
+                        (self.tasks[task_idx].period, task_idx, job.arrival_time)
+                    }
+                })
+                .collect(),
+            policy: self.policy,
+            ready: BTreeSet::new(),
+            deadlines: BTreeSet::new(),
+            missed: vec![false; instances.len()],
+        };
+        Engine::new(&mut instances, config).run(&mut queue, &mut schedule);
+
+        self.instances = instances;
+        self.missed = queue.missed;
+
+        let utilization = utilization(&self.tasks);
+        let bound = liu_layland_bound(self.tasks.len());
+        schedule.note(format!(
+            "utilization: {:.3}, hyperperiod: {}",
+            utilization, hyperperiod
+        ));
+        schedule.note(format!(
+            "Liu & Layland: U {} {:.3}, {}",
+            if utilization <= bound { "<=" } else { ">" },
+            bound,
+            if utilization <= bound {
+                "schedulable by RM"
+            } else {
+                "inconclusive for RM"
+            }
+        ));
+        schedule.note(format!(
+            "EDF: U {} 1, {}",
+            if utilization <= 1.0 { "<=" } else { ">" },
+            if utilization <= 1.0 {
+                "schedulable by EDF"
+            } else {
+                "not schedulable"
+            }
+        ));
+        schedule.note(format!(
+            "Hyperperiod simulation: {} of {} instances missed",
+            self.missed().len(),
+            self.instances.len()
+        ));
+
+        let mut table = format!(
+            "\n| {0: <8} | {1: <6} | {2: <6} | {3: <6} | {4: <6} |",
+            "实例", "释放时间", "截止时间", "完成时间", "结果"
+        );
+
+        for (job, missed) in self.instances.iter().zip(&self.missed) {
+            table += &format!(
+                "\n| {0: <10} | {1: <10} | {2: <10} | {3: <10} | {4: <10} |",
+                job.name,
+                job.arrival_time,
+                job.deadline.unwrap(),
+                job.finish_time.map(|x| x.to_string()).unwrap_or_default(),
+                if *missed { "MISSED" } else { "OK" }
+            );
+        }
+
+        schedule.note(table);
+        schedule
+    }
+}
+
+/// Active instances by the key of the policy, preemptive at every release.
+struct RealtimeQueue {
+    policy: RealtimePolicy,
+    /// (deadline or period, task, release) of every instance, the smallest runs.
+    keys: Vec<(u32, usize, u32)>,
+    ready: BTreeSet<((u32, usize, u32), usize)>,
+    /// (absolute deadline, instance) of the released, unfinished instances.
+    deadlines: BTreeSet<(u32, usize)>,
+    missed: Vec<bool>,
+}
+
+impl Policy for RealtimeQueue {
+    fn enqueue(&mut self, job: usize, ctx: &mut Context) {
+        let deadline = ctx.jobs[job].deadline.unwrap();
+        self.ready.insert((self.keys[job], job));
+        self.deadlines.insert((deadline, job));
+    }
+
+    fn pick(&mut self, _ctx: &mut Context) -> Option<Pick> {
+        let ((key, _, _), job) = self.ready.pop_first()?;
+
+        Some(Pick::new(job).detail(match self.policy {
+            RealtimePolicy::Edf => format!("deadline {}", key),
+            RealtimePolicy::RateMonotonic => format!("period {}", key),
+        }))
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        self.ready.iter().map(|&(_, job)| job).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.ready.len()
+    }
+
+    // PREEMPT: a released instance goes first, or the running one is dropped
+    fn preempts(&mut self, job: usize, ctx: &mut Context) -> Option<String> {
+        if self.missed[job] {
+            return Some(String::new());
+        }
+
+        match self.ready.first() {
+            Some(&(key, idx)) if key < self.keys[job] => Some(format!("by {}", ctx.jobs[idx].name)),
+            _ => None,
+        }
+    }
+
+    fn preempted(&mut self, job: usize, _ctx: &mut Context) {
+        if !self.missed[job] {
+            self.ready.insert((self.keys[job], job));
+        }
+    }
+
+    fn done(&mut self, job: usize, ctx: &mut Context) {
+        let deadline = ctx.jobs[job].deadline.unwrap();
+        self.deadlines.remove(&(deadline, job));
+    }
+
+    fn next_timer(&self, _ctx: &Context) -> Option<u32> {
+        self.deadlines.first().map(|&(deadline, _)| deadline)
+    }
+
+    // MISSED: deadline reached before the instance finished
+    fn timer(&mut self, ctx: &mut Context) {
+        while let Some(&(deadline, idx)) = self.deadlines.first() {
+            if deadline > ctx.now {
+                break;
+            }
+
+            self.deadlines.pop_first();
+            self.ready.remove(&(self.keys[idx], idx));
+            self.missed[idx] = true;
+
+            let job = &ctx.jobs[idx];
+            let detail = format!("{} of {} ticks done", job.served_time, job.service_time);
+            ctx.log("MISSED", Some(idx), &[], Some(detail));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        realtime::{
+            hyperperiod, liu_layland_bound, utilization, PeriodicTask, RealtimePolicy,
+            RealtimeScheduler, TaskError,
+        },
+        SchedulerConfig,
+    };
+
+    #[test]
+    fn test() {
+        let tasks = vec![PeriodicTask::new("T1", 5, 2), PeriodicTask::new("T2", 7, 4)];
+
+        assert_eq!(hyperperiod(&tasks), Some(35));
+        assert!(utilization(&tasks) > liu_layland_bound(2));
+        assert!(utilization(&tasks) <= 1.0);
+
+        let mut scheduler = RealtimeScheduler::new(tasks.clone(), RealtimePolicy::Edf).unwrap();
+        scheduler.run(&SchedulerConfig::default()).print();
+        assert!(scheduler.missed().is_empty());
+        assert_eq!(scheduler.instances.len(), 7 + 5);
+
+        // T2#0 still needs one tick at its deadline 7
+        let mut scheduler = RealtimeScheduler::new(tasks, RealtimePolicy::RateMonotonic).unwrap();
+        scheduler.run(&SchedulerConfig::default()).print();
+        assert_eq!(scheduler.missed()[0].name, "T2#0");
+
+        let late = PeriodicTask {
+            deadline: 6,
+            ..PeriodicTask::new("T3", 5, 2)
+        };
+        assert_eq!(
+            RealtimeScheduler::new(vec![late], RealtimePolicy::Edf).err(),
+            Some(TaskError::Timing("T3".to_string()))
+        );
+        let coprime = vec![
+            PeriodicTask::new("T1", 65_521, 1),
+            PeriodicTask::new("T2", 65_519, 1),
+        ];
+        assert_eq!(hyperperiod(&coprime), Some(65_521 * 65_519));
+        let mut overflowing = coprime;
+        overflowing.push(PeriodicTask::new("T3", 65_537, 1));
+        assert_eq!(
+            RealtimeScheduler::new(overflowing, RealtimePolicy::Edf).err(),
+            Some(TaskError::Hyperperiod)
+        );
+        // 4·10^9 instances of T1 would not fit in memory
+        let dense = vec![
+            PeriodicTask::new("T1", 1, 1),
+            PeriodicTask::new("T2", 4_000_000_000, 1),
+        ];
+        assert_eq!(
+            RealtimeScheduler::new(dense, RealtimePolicy::Edf).err(),
+            Some(TaskError::Instances(4_000_000_001))
+        );
+    }
+}
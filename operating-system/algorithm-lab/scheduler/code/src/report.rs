@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{metrics::Metrics, JobMetrics, Schedule, Segment};
+
+/// Microseconds of a tick in a trace, a viewer shows a tick as a millisecond.
+const TRACE_TICK: u64 = 1000;
+
+/// What a schedule looks like to other programs, without the event log.
+#[derive(Serialize)]
+struct Report<'a> {
+    algorithm: &'a str,
+    jobs: &'a [JobMetrics],
+    segments: &'a [Segment],
+    metrics: Metrics,
+    notes: &'a [String],
+}
+
+/// A JSON array with the jobs, segments, metrics and notes of every schedule.
+pub fn json(schedules: &[Schedule]) -> String {
+    let reports = schedules
+        .iter()
+        .map(|schedule| Report {
+            algorithm: &schedule.algorithm,
+            jobs: &schedule.jobs,
+            segments: &schedule.segments,
+            metrics: schedule.metrics(),
+            notes: &schedule.notes,
+        })
+        .collect::<Vec<Report>>();
+
+    serde_json::to_string_pretty(&reports).unwrap() + "\n"
+}
+
+/// One line per finished job of every schedule, after a header.
+pub fn csv(schedules: &[Schedule]) -> String {
+    let mut csv = String::from(
+        "algorithm,name,arrival_time,service_time,finish_time,whole_time,weight_whole_time,waiting_time,response_time,max_wait,starved\n",
+    );
+
+    for schedule in schedules {
+        for job in &schedule.jobs {
+            csv += &format!(
+                "{},{},{},{},{},{},{:.2},{},{},{},{}\n",
+                schedule.algorithm,
+                job.name,
+                job.arrival_time,
+                job.service_time,
+                job.finish_time,
+                job.whole_time,
+                job.weight_whole_time,
+                job.waiting_time,
+                job.response_time,
+                job.max_wait,
+                job.starved
+            );
+        }
+    }
+
+    csv
+}
+
+/// A Chrome Trace Event Format document.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Trace {
+    trace_events: Vec<TraceEvent>,
+    display_time_unit: &'static str,
+}
+
+/// `ph` is `X` for a span of a track and `M` for the name of a track or process.
+#[derive(Serialize)]
+struct TraceEvent {
+    name: String,
+    ph: &'static str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    cat: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ts: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<u64>,
+    pid: usize,
+    tid: usize,
+    #[serde(skip_serializing_if = "serde_json::Value::is_null")]
+    args: serde_json::Value,
+}
+
+impl TraceEvent {
+    /// Names the process `pid` with `kind` "process_name", or its thread `tid` with
+    /// "thread_name".
+    fn name(kind: &str, pid: usize, tid: usize, name: &str) -> TraceEvent {
+        TraceEvent {
+            name: kind.to_string(),
+            ph: "M",
+            cat: "",
+            ts: None,
+            dur: None,
+            pid,
+            tid,
+            args: json!({ "name": name }),
+        }
+    }
+
+    fn span(
+        name: &str,
+        cat: &'static str,
+        pid: usize,
+        tid: usize,
+        start: u32,
+        end: u32,
+    ) -> TraceEvent {
+        TraceEvent {
+            name: name.to_string(),
+            ph: "X",
+            cat,
+            ts: Some(start as u64 * TRACE_TICK),
+            dur: Some((end - start) as u64 * TRACE_TICK),
+            pid,
+            tid,
+            args: serde_json::Value::Null,
+        }
+    }
+}
+
+/// Every schedule as two processes of a Chrome trace: one with a track per CPU and
+/// the jobs it ran, one with a track per finished job and the ticks it was running,
+/// waiting or blocked. Blocked periods come from the event log.
+pub fn trace(schedules: &[Schedule]) -> String {
+    let mut events = Vec::new();
+
+    for (idx, schedule) in schedules.iter().enumerate() {
+        let (cpus_pid, jobs_pid) = (2 * idx + 1, 2 * idx + 2);
+        let name = format!("{} CPUs", schedule.algorithm);
+        events.push(TraceEvent::name("process_name", cpus_pid, 0, &name));
+        let name = format!("{} jobs", schedule.algorithm);
+        events.push(TraceEvent::name("process_name", jobs_pid, 0, &name));
+
+        for cpu in 0..schedule.cpus() {
+            let name = format!("CPU{}", cpu);
+            events.push(TraceEvent::name("thread_name", cpus_pid, cpu, &name));
+        }
+
+        // (start, end, state) of every job, waiting fills the gaps
+        let mut states: HashMap<&str, Vec<(u32, u32, &'static str)>> = HashMap::new();
+
+        for segment in &schedule.segments {
+            let job = segment.job.as_deref().unwrap();
+            let (name, state) = match segment.overhead {
+                true => ("switch", "switch"),
+                false => (job, "running"),
+            };
+
+            events.push(TraceEvent::span(
+                name,
+                state,
+                cpus_pid,
+                segment.cpu,
+                segment.start,
+                segment.end,
+            ));
+            states
+                .entry(job)
+                .or_default()
+                .push((segment.start, segment.end, state));
+        }
+
+        // BLOCKED: from the end of a CPU burst until the job is back from I/O
+        let mut blocked: HashMap<&str, u32> = HashMap::new();
+        for process in &schedule.events {
+            let Some(job) = &process.job else {
+                continue;
+            };
+
+            match process.status.as_str() {
+                "BLOCKED" => {
+                    blocked.insert(&job.name, process.current_time);
+                }
+                "READY" => {
+                    if let Some(start) = blocked.remove(job.name.as_str()) {
+                        states.entry(&job.name).or_default().push((
+                            start,
+                            process.current_time,
+                            "blocked",
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut jobs = schedule.jobs.iter().collect::<Vec<&JobMetrics>>();
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        for (tid, job) in jobs.into_iter().enumerate() {
+            events.push(TraceEvent::name("thread_name", jobs_pid, tid, &job.name));
+
+            let mut states = states.remove(job.name.as_str()).unwrap_or_default();
+            states.sort();
+
+            let mut time = job.arrival_time;
+            for (start, end, state) in states {
+                if start > time {
+                    events.push(TraceEvent::span(
+                        "waiting", "waiting", jobs_pid, tid, time, start,
+                    ));
+                }
+                if end > start {
+                    events.push(TraceEvent::span(state, state, jobs_pid, tid, start, end));
+                }
+                time = time.max(end);
+            }
+        }
+    }
+
+    let trace = Trace {
+        trace_events: events,
+        display_time_unit: "ms",
+    };
+    serde_json::to_string_pretty(&trace).unwrap() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        build_jobs, fcfs::FcfsScheduler, report, sjf::SjfScheduler, Burst, Job, Scheduler,
+        SchedulerConfig,
+    };
+
+    #[test]
+    fn test() {
+        let config = SchedulerConfig::default();
+        let schedules = vec![
+            FcfsScheduler::new(build_jobs()).run(&config),
+            SjfScheduler::new(build_jobs()).run(&config),
+        ];
+
+        let json: serde_json::Value = serde_json::from_str(&report::json(&schedules)).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert_eq!(json[0]["jobs"][0]["name"], "A");
+        assert_eq!(json[0]["segments"][1]["start"], 5);
+        assert_eq!(json[1]["metrics"]["makespan"], 25);
+
+        let csv = report::csv(&schedules);
+        assert_eq!(csv.lines().count(), 11);
+        assert!(csv.lines().nth(1).unwrap().ends_with(",A,0,5,5,5,1.00,0,0,0,false"));
+
+        // A blocks for I/O from 2 to 5 while B waits for the CPU from 1 to 2
+        let jobs = vec![
+            Job::with_bursts("A", 0, Burst::alternating(&[2, 3, 1])),
+            Job::new("B", 1, 4),
+        ];
+        let schedules = vec![FcfsScheduler::new(jobs).run(&config)];
+
+        let trace: serde_json::Value = serde_json::from_str(&report::trace(&schedules)).unwrap();
+        let spans = trace["traceEvents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|event| event["ph"] == "X" && event["pid"] == 2)
+            .map(|event| {
+                (
+                    event["tid"].as_u64().unwrap(),
+                    event["name"].as_str().unwrap(),
+                    event["ts"].as_u64().unwrap() / 1000,
+                    event["dur"].as_u64().unwrap() / 1000,
+                )
+            })
+            .collect::<Vec<(u64, &str, u64, u64)>>();
+        assert_eq!(
+            spans,
+            vec![
+                (0, "running", 0, 2),
+                (0, "blocked", 2, 3),
+                (0, "waiting", 5, 1),
+                (0, "running", 6, 1),
+                (1, "waiting", 1, 1),
+                (1, "running", 2, 4),
+            ]
+        );
+    }
+}
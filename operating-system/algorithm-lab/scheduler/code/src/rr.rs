@@ -1,125 +1,346 @@
-use crate::{Job, SchedulerProcess, SchedulerResult};
+use std::collections::VecDeque;
+
+use crate::{
+    burst::BurstReport,
+    engine::{Context, Engine, Pick, Policy},
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
 
 pub struct RrScheduler {
-    jobs: Vec<Job>,
-    results: SchedulerResult,
+    pub(crate) jobs: Vec<Job>,
+    /// Set when the jobs had I/O bursts.
+    pub(crate) bursts: Option<BurstReport>,
+    variant: RrVariant,
 }
 
 impl RrScheduler {
-    pub fn new(jobs: Vec<Job>) -> RrScheduler {
-        let mut rr_jobs: Vec<Job> = jobs.clone();
-        rr_jobs.sort_by_key(|job| job.arrival_time);
+    pub fn variant(mut self, variant: RrVariant) -> RrScheduler {
+        self.variant = variant;
+        self
+    }
+}
 
-        RrScheduler {
-            jobs: rr_jobs,
-            results: SchedulerResult::new(),
+/// How long the slice of a job is, decided whenever it starts a new one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RrVariant {
+    /// The configured quantum.
+    Fixed,
+    /// The median of the remaining bursts of the ready jobs, the lower one of two.
+    Median,
+    /// The mean of the remaining bursts of the ready jobs, rounded up.
+    Mean,
+    /// The configured quantum times the levels a job is above the lowest priority
+    /// plus one, the highest priority gets the longest slice.
+    Priority,
+    /// Virtual round robin: a job that blocks before its slice is over comes back
+    /// from I/O to an auxiliary queue served first, with the rest of the slice.
+    Virtual,
+}
+
+/// The slice of a job.
+#[derive(Debug, Clone, Copy, Default)]
+struct Slice {
+    quantum: u32,
+    /// Ticks used of it, kept when a higher queue takes the CPU.
+    used: u32,
+    /// Left of it when the job blocked, for the auxiliary queue.
+    left: u32,
+}
+
+/// One queue in the order the jobs became ready, a job goes to its back when its
+/// slice runs out.
+#[derive(Debug, Clone)]
+pub struct RrQueue {
+    slice_size: u32,
+    variant: RrVariant,
+    queue: VecDeque<usize>,
+    /// Jobs back from I/O with some of their slice left, only in virtual RR.
+    auxiliary: VecDeque<usize>,
+    slices: Vec<Slice>,
+    /// Lowest priority, i.e. the largest value, of the jobs.
+    lowest: Option<u32>,
+}
+
+impl RrQueue {
+    pub fn new(slice_size: u32) -> RrQueue {
+        assert!(slice_size > 0, "slice size must be positive");
+
+        RrQueue {
+            slice_size,
+            variant: RrVariant::Fixed,
+            queue: VecDeque::new(),
+            auxiliary: VecDeque::new(),
+            slices: Vec::new(),
+            lowest: None,
         }
     }
 
-    pub fn run(&mut self, slice_size: u32) {
-        let mut cycle_count = 0;
-        let mut tick_count = 0;
-        let mut _index = 0;
-        let mut finished_count = 0;
+    pub fn variant(mut self, variant: RrVariant) -> RrQueue {
+        self.variant = variant;
+        self
+    }
+
+    fn slice(&mut self, job: usize) -> &mut Slice {
+        if self.slices.len() <= job {
+            self.slices.resize(job + 1, Slice::default());
+        }
+
+        &mut self.slices[job]
+    }
 
-        let task_len = self.jobs.len();
-        let mut running_tasks: Vec<usize> = Vec::new();
-        println!("slice size: {}", slice_size);
-        while finished_count < task_len {
-            let current_queue = running_tasks
+    /// The quantum of a new slice of `job`, with the reason when it is not fixed.
+    fn quantum(&mut self, job: usize, ctx: &Context) -> (u32, Option<String>) {
+        let bursts = || {
+            let mut bursts = std::iter::once(job)
+                .chain(self.queue.iter().copied())
+                .map(|x| ctx.remaining(x))
+                .collect::<Vec<u32>>();
+            bursts.sort();
+            bursts
+        };
+        let list = |bursts: &[u32]| match ctx.logging() {
+            true => bursts
                 .iter()
-                .map(|&x| self.jobs.get(x).unwrap().clone())
-                .collect::<Vec<Job>>();
-
-            // ENQUEUE
-            if _index < task_len {
-                let current_task = self.jobs.get(_index);
-                if let Some(job) = current_task {
-                    if job.arrival_time == cycle_count {
-                        running_tasks.push(_index);
-                        _index += 1;
-                    }
-                }
+                .map(u32::to_string)
+                .collect::<Vec<String>>()
+                .join(" "),
+            false => String::new(),
+        };
+
+        match self.variant {
+            RrVariant::Fixed | RrVariant::Virtual => (self.slice_size, None),
+            RrVariant::Median => {
+                let bursts = bursts();
+                let quantum = bursts[(bursts.len() - 1) / 2];
+                (
+                    quantum,
+                    Some(format!("quantum {} = median of {}", quantum, list(&bursts))),
+                )
             }
+            RrVariant::Mean => {
+                let bursts = bursts();
+                let sum = bursts.iter().map(|&x| x as u64).sum::<u64>();
+                let quantum = sum.div_ceil(bursts.len() as u64) as u32;
+                (
+                    quantum,
+                    Some(format!("quantum {} = mean of {}", quantum, list(&bursts))),
+                )
+            }
+            RrVariant::Priority => {
+                let lowest = *self
+                    .lowest
+                    .get_or_insert_with(|| ctx.jobs.iter().map(|x| x.priority).max().unwrap());
+                let priority = ctx.jobs[job].priority;
+                // saturates for the far ends of the priority range
+                let levels = (lowest - priority).saturating_add(1);
+                let quantum = self.slice_size.saturating_mul(levels);
+                (
+                    quantum,
+                    Some(format!("quantum {} for priority {}", quantum, priority)),
+                )
+            }
+        }
+    }
+}
 
-            while running_tasks.len() == 0 {
-                cycle_count += 1;
+impl Policy for RrQueue {
+    // ENQUEUE: back from I/O with some slice left goes to the auxiliary queue
+    fn enqueue(&mut self, job: usize, _ctx: &mut Context) {
+        match self.variant == RrVariant::Virtual && self.slice(job).left > 0 {
+            true => self.auxiliary.push_back(job),
+            false => self.queue.push_back(job),
+        }
+    }
 
-                self.results.log(SchedulerProcess {
-                    job: None,
-                    status: "WAITING".to_string(),
-                    current_time: cycle_count,
-                    current_queue: current_queue.clone(),
-                });
-                continue;
-            }
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        if let Some(job) = self.auxiliary.pop_front() {
+            let left = self.slice(job).left;
+            *self.slice(job) = Slice {
+                quantum: left,
+                used: 0,
+                left: 0,
+            };
 
-            // FORCE MOVE TO NEXT TASK
-            if tick_count == slice_size {
-                tick_count = 0;
-                let current_index = *running_tasks.first().unwrap();
-                running_tasks.remove(0);
-                running_tasks.push(current_index);
-            }
+            let detail = format!("quantum {} left, auxiliary queue", left);
+            return Some(Pick::new(job).slice(left).detail(detail));
+        }
 
-            // SCHEDULE
-            let current_task_index = *running_tasks.first().unwrap();
-            let current_task = self.jobs.get_mut(current_task_index).unwrap();
-
-            if current_task.arrival_time <= cycle_count {
-                tick_count += 1;
-                current_task.served_time += 1;
-
-                // FINISHED: has finished its job
-                if current_task.served_time == current_task.service_time {
-                    current_task.finish_time = Some(cycle_count + 1);
-                    current_task.whole_time =
-                        Some(current_task.finish_time.unwrap() - current_task.arrival_time);
-                    current_task.weight_whole_time = Some(
-                        current_task.whole_time.unwrap() as f32 / current_task.service_time as f32,
-                    );
-
-                    tick_count = 0;
-                    finished_count += 1;
-                    running_tasks.remove(0);
-                }
-
-                self.results.log(SchedulerProcess {
-                    job: Some(current_task.clone()),
-                    status: match current_task.served_time == current_task.service_time {
-                        true => "FINISHED".to_string(),
-                        false => "RUNNING".to_string(),
-                    },
-                    current_time: cycle_count,
-                    current_queue: current_queue.clone(),
-                });
-
-                // TASKS HAS ALL FINISHED
-                if finished_count >= task_len {
-                    break;
-                }
-            }
+        let job = self.queue.pop_front()?;
+        let mut detail = None;
 
-            cycle_count += 1;
+        // a new slice unless a higher queue cut the last one short
+        if self.slice(job).used == 0 {
+            let (quantum, reason) = self.quantum(job, ctx);
+            self.slice(job).quantum = quantum;
+            detail = reason;
         }
+
+        let slice = self.slice(job);
+        let pick = Pick::new(job).slice(slice.quantum - slice.used);
+        Some(match detail {
+            Some(detail) => pick.detail(detail),
+            None => pick,
+        })
     }
 
-    pub fn print(&mut self) {
-        println!("\n\n[Scheduler algorithm: RR（时间片轮转)]");
-        self.results.print();
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        self.auxiliary
+            .iter()
+            .chain(self.queue.iter())
+            .copied()
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.auxiliary.len() + self.queue.len()
+    }
+
+    fn ran(&mut self, job: usize, ticks: u32, _ctx: &mut Context) {
+        self.slice(job).used += ticks;
+    }
+
+    fn expired(&mut self, job: usize, _ctx: &mut Context) {
+        self.slice(job).used = 0;
+        self.queue.push_back(job);
+    }
+
+    /// Taken off by a higher queue, it goes on first with the rest of its slice.
+    fn preempted(&mut self, job: usize, _ctx: &mut Context) {
+        self.queue.push_front(job);
+    }
+
+    fn done(&mut self, job: usize, _ctx: &mut Context) {
+        let slice = self.slice(job);
+        slice.left = slice.quantum - slice.used;
+        slice.used = 0;
+    }
+}
+
+impl Scheduler for RrScheduler {
+    fn new(jobs: Vec<Job>) -> RrScheduler {
+        let mut rr_jobs: Vec<Job> = jobs.clone();
+        rr_jobs.sort_by_key(|job| job.arrival_time);
+
+        RrScheduler {
+            jobs: rr_jobs,
+            bursts: None,
+            variant: RrVariant::Fixed,
+        }
+    }
+
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new(match self.variant {
+            RrVariant::Fixed => "RR（时间片轮转)",
+            RrVariant::Median => "RR（中位数时间片）",
+            RrVariant::Mean => "RR（平均数时间片）",
+            RrVariant::Priority => "RR（优先级时间片）",
+            RrVariant::Virtual => "VRR（虚拟时间片轮转）",
+        });
+        let slice_size = config.quantum;
+        schedule.note(format!("slice size: {}", slice_size));
+
+        let mut queue = RrQueue::new(slice_size).variant(self.variant);
+        let bursts = Engine::new(&mut self.jobs, config).run(&mut queue, &mut schedule);
+
+        if self.jobs.iter().any(Job::has_io) {
+            schedule.note(bursts.to_string());
+            self.bursts = Some(bursts);
+        }
+
+        schedule
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{build_jobs, rr::RrScheduler, Job};
+    use crate::{
+        build_jobs,
+        rr::{RrScheduler, RrVariant},
+        Burst, Job, Schedule, Scheduler, SchedulerConfig,
+    };
+
+    /// Details of the dispatches of `job`.
+    fn decisions<'a>(schedule: &'a Schedule, job: &str) -> Vec<(u32, &'a str)> {
+        schedule
+            .events
+            .iter()
+            .filter(|process| {
+                process.status == "RUNNING" && process.job.as_ref().unwrap().name == job
+            })
+            .map(|process| {
+                (
+                    process.current_time,
+                    process.detail.as_deref().unwrap_or(""),
+                )
+            })
+            .collect()
+    }
 
     #[test]
     fn test() {
         let jobs: Vec<Job> = build_jobs();
 
-        let mut scheduler = RrScheduler::new(jobs);
-        scheduler.run(2);
-        scheduler.print();
+        let mut scheduler: Box<dyn Scheduler> = Box::new(RrScheduler::new(jobs));
+        let schedule = scheduler.run(&SchedulerConfig::default().quantum(2));
+        schedule.print();
+
+        let finish_times = schedule
+            .jobs
+            .iter()
+            .map(|job| (job.name.as_str(), job.finish_time))
+            .collect::<Vec<(&str, u32)>>();
+        assert_eq!(
+            finish_times,
+            vec![("E", 14), ("A", 15), ("C", 16), ("B", 23), ("D", 25)]
+        );
+        assert_eq!(schedule.average_whole_time(), 15.8);
+
+        let config = SchedulerConfig::default();
+        let schedule = RrScheduler::new(build_jobs())
+            .variant(RrVariant::Median)
+            .run(&config);
+        schedule.print();
+        assert_eq!(decisions(&schedule, "A")[0], (0, "quantum 5 = median of 5"));
+        assert_eq!(schedule.jobs.len(), 5);
+
+        // C has the highest priority 1 of 1 to 4, so 4 levels of slice
+        let schedule = RrScheduler::new(build_jobs())
+            .variant(RrVariant::Priority)
+            .run(&config);
+        assert_eq!(decisions(&schedule, "C")[0].1, "quantum 4 for priority 1");
+        assert_eq!(decisions(&schedule, "D")[0].1, "quantum 1 for priority 4");
+        let jobs = vec![
+            Job::new("A", 1, 3),
+            Job {
+                priority: u32::MAX,
+                ..Job::new("B", 0, 3)
+            },
+        ];
+        let schedule = RrScheduler::new(jobs)
+            .variant(RrVariant::Priority)
+            .run(&SchedulerConfig::default().quantum(2));
+        assert_eq!(
+            decisions(&schedule, "A")[0].1,
+            format!("quantum {} for priority 0", u32::MAX)
+        );
+
+        // IO blocks after 1 of its 3 ticks and comes back ahead of CPU with 2 left
+        let jobs = vec![
+            Job::with_bursts("IO", 0, Burst::alternating(&[1, 2, 3])),
+            Job::new("CPU", 0, 10),
+        ];
+        let mut scheduler = RrScheduler::new(jobs).variant(RrVariant::Virtual);
+        let schedule = scheduler.run(&config.quantum(3));
+        schedule.print();
+        assert_eq!(
+            decisions(&schedule, "IO")[1],
+            (4, "quantum 2 left, auxiliary queue")
+        );
+        let finish_times = scheduler
+            .jobs
+            .iter()
+            .map(|job| job.finish_time.unwrap())
+            .collect::<Vec<u32>>();
+        assert_eq!(finish_times, vec![10, 14]);
     }
 }
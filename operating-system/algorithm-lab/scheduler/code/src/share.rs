@@ -0,0 +1,123 @@
+use std::fmt;
+
+use crate::Job;
+
+/// Compares the CPU time every job got with the share its tickets entitle it to.
+///
+/// At every tick each competing job is entitled to `tickets / tickets of all
+/// competing jobs` of that tick, so a job is only measured against the jobs it
+/// competed with. A job competes from `join` until `leave`. A report of groups
+/// does the same with the weight of every group instead of tickets.
+pub struct ShareReport {
+    /// Headers of the name and the tickets column.
+    headers: [&'static str; 2],
+    names: Vec<String>,
+    tickets: Vec<u32>,
+    entitled: Vec<f64>,
+    received: Vec<u32>,
+    /// Tickets of the competing jobs.
+    competing: u64,
+    /// Ticks every ticket has been entitled to so far, a tick adds `1 / competing`.
+    per_ticket: f64,
+    /// `per_ticket` when a competing job joined.
+    joined: Vec<Option<f64>>,
+}
+
+impl ShareReport {
+    pub fn new(jobs: &[Job]) -> ShareReport {
+        ShareReport {
+            headers: ["名称", "票数"],
+            names: jobs.iter().map(|job| job.name.clone()).collect(),
+            tickets: jobs.iter().map(|job| job.tickets).collect(),
+            entitled: vec![0.0; jobs.len()],
+            received: vec![0; jobs.len()],
+            competing: 0,
+            per_ticket: 0.0,
+            joined: vec![None; jobs.len()],
+        }
+    }
+
+    /// The groups called `names`, entitled to the CPU by their `weights`.
+    pub fn groups(names: Vec<String>, weights: Vec<u32>) -> ShareReport {
+        let len = names.len();
+
+        ShareReport {
+            headers: ["组", "权重"],
+            names,
+            tickets: weights,
+            entitled: vec![0.0; len],
+            received: vec![0; len],
+            competing: 0,
+            per_ticket: 0.0,
+            joined: vec![None; len],
+        }
+    }
+
+    /// `idx` competes for the CPU from now on.
+    pub fn join(&mut self, idx: usize) {
+        if self.joined[idx].is_none() {
+            self.joined[idx] = Some(self.per_ticket);
+            self.competing += self.tickets[idx] as u64;
+        }
+    }
+
+    /// `idx` stops competing, it finished or blocks.
+    pub fn leave(&mut self, idx: usize) {
+        if let Some(joined) = self.joined[idx].take() {
+            self.entitled[idx] += self.tickets[idx] as f64 * (self.per_ticket - joined);
+            self.competing -= self.tickets[idx] as u64;
+        }
+    }
+
+    /// Records `ticks` given to `running` while the joined jobs competed.
+    pub fn ran(&mut self, running: usize, ticks: u32) {
+        self.per_ticket += ticks as f64 / self.competing as f64;
+        self.received[running] += ticks;
+    }
+
+    pub fn entitled(&self, idx: usize) -> f32 {
+        let competing = self.joined[idx].map_or(0.0, |joined| {
+            self.tickets[idx] as f64 * (self.per_ticket - joined)
+        });
+
+        (self.entitled[idx] + competing) as f32
+    }
+
+    pub fn received(&self, idx: usize) -> u32 {
+        self.received[idx]
+    }
+}
+
+impl fmt::Display for ShareReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let busy = self.received.iter().sum::<u32>() as f32;
+
+        write!(
+            f,
+            "\n| {0: <8} | {1: <6} | {2: <10} | {3: <10} | {4: <6} |",
+            self.headers[0], self.headers[1], "应得时间", "实际时间", "实际/应得"
+        )?;
+
+        for idx in 0..self.names.len() {
+            write!(
+                f,
+                "\n| {0: <10} | {1: <8} | {2: <14} | {3: <14} | {4: <10} |",
+                self.names[idx],
+                self.tickets[idx],
+                format!(
+                    "{:.2} ({:.1}%)",
+                    self.entitled(idx),
+                    self.entitled(idx) / busy * 100.0
+                ),
+                format!(
+                    "{} ({:.1}%)",
+                    self.received[idx],
+                    self.received[idx] as f32 / busy * 100.0
+                ),
+                format!("{:.2}", self.received[idx] as f32 / self.entitled(idx)),
+            )?;
+        }
+
+        Ok(())
+    }
+}
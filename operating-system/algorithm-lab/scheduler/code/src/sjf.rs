@@ -1,75 +1,140 @@
-use crate::{Job, Scheduler, SchedulerProcess, SchedulerResult};
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::{
+    burst::BurstReport,
+    engine::{Context, Engine, Pick, Policy},
+    Aging, Job, Schedule, Scheduler, SchedulerConfig,
+};
 
 pub struct SjfScheduler {
-    jobs: Vec<Job>,
-    results: SchedulerResult,
+    pub(crate) jobs: Vec<Job>,
+    /// Set when the jobs had I/O bursts.
+    pub(crate) bursts: Option<BurstReport>,
+}
+
+/// Picks the shortest next CPU burst, the one that became ready first on a tie. A job
+/// keeps the CPU for its whole burst.
+#[derive(Debug, Clone, Default)]
+pub struct SjfQueue {
+    /// (burst, enqueued as, job)
+    ready: BTreeSet<(u32, u64, usize)>,
+    /// Taken off by a higher queue, they go on before any shorter job.
+    resumed: VecDeque<usize>,
+    enqueued: u64,
+    /// Shortens the burst a job is picked by while it waits, every pick scans the
+    /// queue then.
+    aging: Option<Aging>,
+}
+
+impl SjfQueue {
+    pub fn aging(mut self, aging: Option<Aging>) -> SjfQueue {
+        self.aging = aging;
+        self
+    }
+}
+
+impl Policy for SjfQueue {
+    fn enqueue(&mut self, job: usize, ctx: &mut Context) {
+        self.ready.insert((ctx.remaining(job), self.enqueued, job));
+        self.enqueued += 1;
+    }
+
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        if let Some(job) = self.resumed.pop_front() {
+            return Some(Pick::new(job));
+        }
+
+        let Some(aging) = self.aging else {
+            return self.ready.pop_first().map(|(_, _, job)| Pick::new(job));
+        };
+
+        // AGING: the shortest burst after the ticks every job waited
+        let aged = |&(burst, seq, job): &(u32, u64, usize)| {
+            (aging.aged(burst, ctx.now - ctx.ready_since(job)), seq, job)
+        };
+        let first = self.ready.iter().min_by_key(|entry| aged(entry)).copied()?;
+        self.ready.remove(&first);
+
+        let (burst, _, job) = first;
+        let (aged, _, _) = aged(&first);
+        Some(match aged < burst {
+            true => Pick::new(job).detail(format!("aged {} -> {}", burst, aged)),
+            false => Pick::new(job),
+        })
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        let ready = self.ready.iter().map(|&(_, _, job)| job);
+
+        self.resumed.iter().copied().chain(ready).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.resumed.len() + self.ready.len()
+    }
+
+    fn preempted(&mut self, job: usize, _ctx: &mut Context) {
+        self.resumed.push_front(job);
+    }
 }
 
 impl Scheduler for SjfScheduler {
     fn new(mut jobs: Vec<Job>) -> SjfScheduler {
         jobs.sort_by_key(|job| job.arrival_time);
 
-        SjfScheduler {
-            jobs: jobs,
-            results: SchedulerResult::new(),
-        }
+        SjfScheduler { jobs, bursts: None }
     }
 
-    fn run(&mut self) {
-        let mut current_time: u32 = 0;
-        let mut running_queue = self.jobs.iter().map(|x| x.clone()).collect::<Vec<Job>>();
-
-        while running_queue.len() > 0 {
-            let current_running_queue = running_queue.iter().filter_map(|x| match x.arrival_time <= current_time && x.finish_time.is_none() {
-                true => Some(x.clone()),
-                false => None,
-            }).collect::<Vec<Job>>();
-
-            let current_job = running_queue
-                .iter_mut()
-                .enumerate()
-                .filter(|(_, x) | x.arrival_time <= current_time && x.finish_time.is_none())
-                .min_by_key(|(_, x)| x.service_time);
-
-            match current_job {
-                None => current_time += 1,
-                Some((idx, job)) => {
-                    job.finish_time = Some(current_time + job.service_time);
-                    job.whole_time = Some(job.finish_time.unwrap() - job.arrival_time);
-                    job.weight_whole_time =
-                        Some(job.whole_time.unwrap() as f32 / job.service_time as f32);
-
-                    self.results.log(SchedulerProcess {
-                        job: Some(job.clone()),
-                        current_time,
-                        status: "FINISHED".to_string(),
-                        current_queue: current_running_queue,
-
-                    });
-
-                    current_time += job.service_time;
-                    running_queue.remove(idx);
-                }
-            }
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("SJF（短作业优先）");
+
+        let mut queue = SjfQueue::default().aging(config.aging);
+        let bursts = Engine::new(&mut self.jobs, config).run(&mut queue, &mut schedule);
+
+        if let Some(aging) = config.aging {
+            schedule.note(aging.to_string());
+        }
+
+        if self.jobs.iter().any(Job::has_io) {
+            schedule.note(bursts.to_string());
+            self.bursts = Some(bursts);
         }
-    }
 
-    fn print(&mut self) {
-        println!("\n\n[Scheduler algorithm: SJF（短作业优先）]");
-        self.results.print();
+        schedule
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{build_jobs, sjf::SjfScheduler, Job, Scheduler};
+    use crate::{build_jobs, sjf::SjfScheduler, Aging, Job, Scheduler, SchedulerConfig};
 
     #[test]
     fn test() {
         let jobs: Vec<Job> = build_jobs();
 
         let mut scheduler = SjfScheduler::new(jobs);
-        scheduler.run();
-        scheduler.print();
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+
+        // every short job passes L unless L ages a tick of its burst per tick waited
+        let jobs = vec![
+            Job::new("X", 0, 2),
+            Job::new("L", 1, 6),
+            Job::new("S1", 2, 2),
+            Job::new("S2", 4, 2),
+            Job::new("S3", 6, 2),
+            Job::new("S4", 8, 2),
+        ];
+        let config = SchedulerConfig::default().starvation(6);
+
+        let schedule = SjfScheduler::new(jobs.clone()).run(&config);
+        let l = schedule.job("L").unwrap();
+        assert_eq!((l.finish_time, l.max_wait, l.starved), (16, 9, true));
+
+        let schedule = SjfScheduler::new(jobs).run(&config.aging(Aging::new(1, 10)));
+        schedule.print();
+        let l = schedule.job("L").unwrap();
+        assert_eq!((l.finish_time, l.max_wait), (12, 5));
+        assert!(schedule.jobs.iter().all(|job| !job.starved));
     }
 }
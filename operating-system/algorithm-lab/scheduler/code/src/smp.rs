@@ -0,0 +1,434 @@
+use std::{collections::VecDeque, fmt};
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    mlq::QueuePolicy,
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+/// How the ready jobs are shared between the CPUs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueMode {
+    /// One ready queue every CPU takes from.
+    Global,
+    /// Every CPU has its own ready queue, a new job goes to the least loaded one.
+    PerCpu(Balancing),
+}
+
+/// Load balancing between per-CPU queues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Balancing {
+    /// A job stays on the queue it was given.
+    None,
+    /// Every `interval` ticks jobs are pulled from the longest queue to the shortest.
+    Periodic(u32),
+    /// A CPU with an empty queue takes a job from the tail of the longest one.
+    WorkStealing,
+}
+
+/// A job may run on a CPU the scheduler does not have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffinityError {
+    pub job: String,
+    pub cpu: usize,
+    pub cpus: usize,
+}
+
+impl fmt::Display for AffinityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} may run on CPU{}, but there are only {} CPUs",
+            self.job, self.cpu, self.cpus
+        )
+    }
+}
+
+impl std::error::Error for AffinityError {}
+
+/// Checks that the affinity of every job names only CPUs below `cpus`.
+pub fn check_affinity(jobs: &[Job], cpus: usize) -> Result<(), AffinityError> {
+    for job in jobs {
+        if let Some(&cpu) = job.affinity.iter().find(|&&cpu| cpu >= cpus) {
+            return Err(AffinityError {
+                job: job.name.clone(),
+                cpu,
+                cpus,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// `cpus` processors sharing the engine, every queue is ordered by `policy`.
+pub struct SmpScheduler {
+    jobs: Vec<Job>,
+    cpus: usize,
+    mode: QueueMode,
+    policy: QueuePolicy,
+    /// Ticks every CPU spent running a job.
+    busy: Vec<u32>,
+    /// Times a job continued on another CPU than the one it last ran on.
+    migrations: u32,
+    makespan: u32,
+}
+
+impl SmpScheduler {
+    pub fn cpus(mut self, cpus: usize) -> SmpScheduler {
+        assert!(cpus > 0, "needs at least one CPU");
+
+        self.cpus = cpus;
+        self
+    }
+
+    pub fn mode(mut self, mode: QueueMode) -> SmpScheduler {
+        if let QueueMode::PerCpu(Balancing::Periodic(interval)) = mode {
+            assert!(interval > 0, "balancing interval must be positive");
+        }
+
+        self.mode = mode;
+        self
+    }
+
+    pub fn policy(mut self, policy: QueuePolicy) -> SmpScheduler {
+        if let QueuePolicy::Rr(slice_size) = policy {
+            assert!(slice_size > 0, "slice size must be positive");
+        }
+
+        self.policy = policy;
+        self
+    }
+
+    pub fn migrations(&self) -> u32 {
+        self.migrations
+    }
+
+    /// Share of the makespan `cpu` was busy.
+    pub fn utilization(&self, cpu: usize) -> f32 {
+        match self.makespan {
+            0 => 0.0,
+            makespan => self.busy[cpu] as f32 / makespan as f32,
+        }
+    }
+}
+
+/// The ready queues, one for all CPUs or one per CPU.
+struct SmpQueues {
+    mode: QueueMode,
+    policy: QueuePolicy,
+    queues: Vec<VecDeque<usize>>,
+    last_cpu: Vec<Option<usize>>,
+    busy: Vec<u32>,
+    migrations: u32,
+}
+
+impl SmpQueues {
+    fn queue_of(&self, cpu: usize) -> usize {
+        match self.mode {
+            QueueMode::Global => 0,
+            QueueMode::PerCpu(..) => cpu,
+        }
+    }
+
+    /// Position in `queue` of the job `cpu` runs next.
+    fn position(&self, queue: &VecDeque<usize>, ctx: &Context) -> Option<usize> {
+        let mut allowed = queue
+            .iter()
+            .enumerate()
+            .filter(|(_, &x)| ctx.jobs[x].can_run_on(ctx.cpu));
+
+        match self.policy {
+            QueuePolicy::Fcfs | QueuePolicy::Rr(..) => allowed.next().map(|(pos, _)| pos),
+            QueuePolicy::Sjf => allowed
+                .min_by_key(|(_, &x)| (ctx.remaining(x), x))
+                .map(|(pos, _)| pos),
+        }
+    }
+
+    /// Queue and position of the job an idle `cpu` steals, the longest queue first.
+    fn steal(&self, ctx: &Context) -> Option<(usize, usize)> {
+        let mut victims = (0..self.queues.len())
+            .filter(|&x| x != ctx.cpu)
+            .collect::<Vec<usize>>();
+        victims.sort_by_key(|&x| std::cmp::Reverse(self.queues[x].len()));
+
+        victims.into_iter().find_map(|victim| {
+            self.queues[victim]
+                .iter()
+                .rposition(|&x| ctx.jobs[x].can_run_on(ctx.cpu))
+                .map(|pos| (victim, pos))
+        })
+    }
+}
+
+impl Policy for SmpQueues {
+    // ENQUEUE: to the least loaded CPU the job may run on
+    fn enqueue(&mut self, job: usize, ctx: &mut Context) {
+        let queue = match self.mode {
+            QueueMode::Global => 0,
+            QueueMode::PerCpu(..) => (0..ctx.cpus())
+                .filter(|&cpu| ctx.jobs[job].can_run_on(cpu))
+                .min_by_key(|&cpu| self.queues[cpu].len() + ctx.running(cpu).iter().count())
+                .unwrap(),
+        };
+
+        self.queues[queue].push_back(job);
+    }
+
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        let cpu = ctx.cpu;
+        let queue = self.queue_of(cpu);
+        let mut picked = self.position(&self.queues[queue], ctx);
+
+        // STEAL: nothing to run here, take from another queue
+        if picked.is_none() && self.mode == QueueMode::PerCpu(Balancing::WorkStealing) {
+            if let Some((victim, pos)) = self.steal(ctx) {
+                let idx = self.queues[victim].remove(pos).unwrap();
+                self.queues[cpu].push_back(idx);
+                picked = Some(self.queues[cpu].len() - 1);
+
+                let detail = format!("from CPU{}", victim);
+                ctx.log("STOLEN", Some(idx), &[], Some(detail));
+            }
+        }
+
+        let idx = self.queues[queue].remove(picked?).unwrap();
+
+        // MIGRATED: continues on another CPU than last time
+        if let Some(previous) = self.last_cpu[idx] {
+            if previous != cpu {
+                self.migrations += 1;
+                let detail = format!("from CPU{}", previous);
+                ctx.log("MIGRATED", Some(idx), &[], Some(detail));
+            }
+        }
+        self.last_cpu[idx] = Some(cpu);
+
+        match self.policy {
+            QueuePolicy::Rr(slice_size) => Some(Pick::new(idx).slice(slice_size)),
+            _ => Some(Pick::new(idx)),
+        }
+    }
+
+    fn queue(&self, cpu: usize) -> Vec<usize> {
+        self.queues[self.queue_of(cpu)].iter().copied().collect()
+    }
+
+    fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    fn ran(&mut self, _job: usize, ticks: u32, ctx: &mut Context) {
+        self.busy[ctx.cpu] += ticks;
+    }
+
+    fn expired(&mut self, job: usize, ctx: &mut Context) {
+        let queue = self.queue_of(ctx.cpu);
+        self.queues[queue].push_back(job);
+    }
+
+    fn next_timer(&self, ctx: &Context) -> Option<u32> {
+        let QueueMode::PerCpu(Balancing::Periodic(interval)) = self.mode else {
+            return None;
+        };
+        let busy = (0..ctx.cpus()).any(|cpu| ctx.running(cpu).is_some());
+
+        (busy || !Policy::is_empty(self)).then_some((ctx.now / interval + 1) * interval)
+    }
+
+    // BALANCE: pulls jobs from the most to the least loaded CPU until they differ by one
+    fn timer(&mut self, ctx: &mut Context) {
+        let cpus = ctx.cpus();
+
+        loop {
+            let loads = (0..cpus)
+                .map(|cpu| self.queues[cpu].len() + ctx.running(cpu).iter().count())
+                .collect::<Vec<usize>>();
+            let busiest = (0..cpus).max_by_key(|&cpu| loads[cpu]).unwrap();
+            let idlest = (0..cpus).min_by_key(|&cpu| loads[cpu]).unwrap();
+
+            if loads[busiest] <= loads[idlest] + 1 {
+                break;
+            }
+
+            // the pair is left unbalanced if affinity pins every queued job
+            let pos = match self.queues[busiest]
+                .iter()
+                .rposition(|&x| ctx.jobs[x].can_run_on(idlest))
+            {
+                Some(pos) => pos,
+                None => break,
+            };
+
+            let idx = self.queues[busiest].remove(pos).unwrap();
+            self.queues[idlest].push_back(idx);
+
+            ctx.cpu = idlest;
+            let detail = format!("CPU{} -> CPU{}", busiest, idlest);
+            ctx.log("BALANCED", Some(idx), &[], Some(detail));
+        }
+    }
+}
+
+impl Scheduler for SmpScheduler {
+    fn new(mut jobs: Vec<Job>) -> SmpScheduler {
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        SmpScheduler {
+            jobs,
+            cpus: 2,
+            mode: QueueMode::Global,
+            policy: QueuePolicy::Fcfs,
+            busy: Vec::new(),
+            migrations: 0,
+            makespan: 0,
+        }
+    }
+
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("SMP（多处理器调度）");
+
+        // CPUs that do not exist are left out of the affinity, see `check_affinity`
+        let cpus = self.cpus;
+        for job in self.jobs.iter_mut() {
+            if let Err(why) = check_affinity(std::slice::from_ref(job), cpus) {
+                schedule.note(format!("{}, ignored", why));
+                job.affinity.retain(|&cpu| cpu < cpus);
+            }
+        }
+
+        let queue_len = match self.mode {
+            QueueMode::Global => 1,
+            QueueMode::PerCpu(..) => self.cpus,
+        };
+
+        let mut queues = SmpQueues {
+            mode: self.mode,
+            policy: self.policy,
+            queues: vec![VecDeque::new(); queue_len],
+            last_cpu: vec![None; self.jobs.len()],
+            busy: vec![0; self.cpus],
+            migrations: 0,
+        };
+        let report = Engine::new(&mut self.jobs, config)
+            .cpus(self.cpus)
+            .run(&mut queues, &mut schedule);
+
+        self.busy = queues.busy;
+        self.migrations = queues.migrations;
+        self.makespan = report.makespan;
+
+        schedule.note(format!(
+            "cpus: {}, mode: {:?}, policy: {:?}",
+            self.cpus, self.mode, self.policy
+        ));
+
+        let mut table = format!(
+            "\n| {0: <6} | {1: <6} | {2: <6} |",
+            "处理器", "忙碌时间", "利用率"
+        );
+
+        for cpu in 0..self.cpus {
+            table += &format!(
+                "\n| CPU{0: <6} | {1: <10} | {2: <9.1}% |",
+                cpu,
+                self.busy[cpu],
+                self.utilization(cpu) * 100.0
+            );
+        }
+
+        schedule.note(table);
+        schedule.note(format!(
+            "完成时间: {}, 迁移次数: {}",
+            self.makespan, self.migrations
+        ));
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        build_jobs,
+        mlq::QueuePolicy,
+        smp::{check_affinity, Balancing, QueueMode, SmpScheduler},
+        Job, Scheduler, SchedulerConfig,
+    };
+
+    fn finish_times(scheduler: &SmpScheduler) -> Vec<u32> {
+        scheduler
+            .jobs
+            .iter()
+            .map(|job| job.finish_time.unwrap())
+            .collect::<Vec<u32>>()
+    }
+
+    #[test]
+    fn test() {
+        let mut scheduler = SmpScheduler::new(build_jobs());
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+        assert_eq!(finish_times(&scheduler), vec![5, 8, 8, 16, 10]);
+        assert_eq!(scheduler.migrations(), 0);
+
+        // jobs rotate over the CPUs, B may only run on CPU1
+        let mut jobs = build_jobs();
+        jobs[1].affinity = vec![1];
+
+        let mut scheduler = SmpScheduler::new(jobs).policy(QueuePolicy::Rr(1));
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+        assert!(scheduler.migrations() > 0);
+        assert!(schedule
+            .events
+            .iter()
+            .filter(|process| process.job.as_ref().is_some_and(|job| job.name == "B"))
+            .all(|process| process.cpu == 1));
+        assert_eq!(scheduler.busy.iter().sum::<u32>(), 25);
+
+        // there is no CPU5, so C may run anywhere
+        let mut jobs = build_jobs();
+        jobs[2].affinity = vec![5];
+        assert_eq!(
+            check_affinity(&jobs, 2).unwrap_err().to_string(),
+            "C may run on CPU5, but there are only 2 CPUs"
+        );
+
+        let mut scheduler = SmpScheduler::new(jobs).mode(QueueMode::PerCpu(Balancing::None));
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        assert_eq!(scheduler.jobs[2].finish_time, Some(8));
+        assert!(schedule
+            .notes
+            .contains(&"C may run on CPU5, but there are only 2 CPUs, ignored".to_string()));
+    }
+
+    #[test]
+    fn balancing() {
+        // D is queued behind A on CPU0 while CPU1 runs out of work at 3
+        let jobs: Vec<Job> = vec![
+            Job::new("A", 0, 6),
+            Job::new("B", 0, 1),
+            Job::new("C", 1, 2),
+            Job::new("D", 1, 2),
+        ];
+
+        let mut scheduler =
+            SmpScheduler::new(jobs.clone()).mode(QueueMode::PerCpu(Balancing::None));
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+        assert_eq!(finish_times(&scheduler), vec![6, 1, 3, 8]);
+
+        let mut scheduler =
+            SmpScheduler::new(jobs.clone()).mode(QueueMode::PerCpu(Balancing::WorkStealing));
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+        assert_eq!(finish_times(&scheduler), vec![6, 1, 3, 5]);
+
+        let mut scheduler = SmpScheduler::new(jobs).mode(QueueMode::PerCpu(Balancing::Periodic(3)));
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+        assert_eq!(finish_times(&scheduler), vec![6, 1, 3, 5]);
+        assert!((scheduler.utilization(0) - 1.0).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+pub struct SrtfScheduler {
+    jobs: Vec<Job>,
+}
+
+/// Ready jobs by the time left of their CPU burst, the one that arrived first on a
+/// tie.
+#[derive(Debug, Clone, Default)]
+struct SrtfQueue {
+    /// (remaining, job)
+    ready: BTreeSet<(u32, usize)>,
+}
+
+impl Policy for SrtfQueue {
+    fn enqueue(&mut self, job: usize, ctx: &mut Context) {
+        self.ready.insert((ctx.remaining(job), job));
+    }
+
+    fn pick(&mut self, _ctx: &mut Context) -> Option<Pick> {
+        self.ready.pop_first().map(|(_, job)| Pick::new(job))
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        self.ready.iter().map(|&(_, job)| job).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.ready.len()
+    }
+
+    // PREEMPTED: a shorter job arrived, the running job wins ties
+    fn preempts(&mut self, job: usize, ctx: &mut Context) -> Option<String> {
+        self.ready
+            .first()
+            .filter(|&&(remaining, _)| remaining < ctx.remaining(job))
+            .map(|&(_, shorter)| format!("by {}", ctx.jobs[shorter].name))
+    }
+}
+
+impl Scheduler for SrtfScheduler {
+    fn new(mut jobs: Vec<Job>) -> SrtfScheduler {
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        SrtfScheduler { jobs }
+    }
+
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("SRTF（最短剩余时间优先）");
+
+        Engine::new(&mut self.jobs, config).run(&mut SrtfQueue::default(), &mut schedule);
+
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_jobs, srtf::SrtfScheduler, Job, Scheduler, SchedulerConfig};
+
+    #[test]
+    fn test() {
+        let jobs: Vec<Job> = build_jobs();
+
+        let mut scheduler = SrtfScheduler::new(jobs);
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+
+        let finish_times = scheduler
+            .jobs
+            .iter()
+            .map(|job| (job.name.as_str(), job.finish_time.unwrap()))
+            .collect::<Vec<(&str, u32)>>();
+        assert_eq!(
+            finish_times,
+            vec![("A", 5), ("B", 17), ("C", 8), ("D", 25), ("E", 10)]
+        );
+    }
+
+    #[test]
+    fn preempt() {
+        let mut jobs: Vec<Job> = build_jobs();
+        // a short job arriving while A still has 4 units left
+        jobs[1].service_time = 1;
+
+        let mut scheduler = SrtfScheduler::new(jobs);
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+
+        let preempted = schedule
+            .events
+            .iter()
+            .filter(|process| process.status == "PREEMPTED")
+            .map(|process| (process.job.as_ref().unwrap().name.as_str(), process.current_time))
+            .collect::<Vec<(&str, u32)>>();
+        assert_eq!(preempted, vec![("A", 1)]);
+        assert_eq!(scheduler.jobs[1].finish_time, Some(2));
+    }
+}
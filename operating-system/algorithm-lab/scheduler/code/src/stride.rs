@@ -0,0 +1,177 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    engine::{Context, Engine, Pick, Policy},
+    share::ShareReport,
+    Job, Schedule, Scheduler, SchedulerConfig,
+};
+
+/// Divided by the tickets of a job to get its stride, so it is also the most tickets
+/// a job may have.
+pub const STRIDE1: u64 = 1 << 20;
+
+pub struct StrideScheduler {
+    jobs: Vec<Job>,
+    share: ShareReport,
+}
+
+/// Ready jobs by pass, the running job's pass grows by its stride every tick.
+struct StrideQueue {
+    quantum: u32,
+    strides: Vec<u64>,
+    passes: Vec<u64>,
+    // the smallest pass of the competing jobs, given to new arrivals
+    global_pass: u64,
+    /// (pass, job)
+    ready: BTreeSet<(u64, usize)>,
+    running: Option<usize>,
+    share: ShareReport,
+}
+
+impl Policy for StrideQueue {
+    // ENQUEUE: start at the global pass so a new job can't monopolize the CPU
+    fn enqueue(&mut self, job: usize, _ctx: &mut Context) {
+        let ready = self.ready.first().map(|&(pass, _)| pass);
+        let running = self.running.map(|idx| self.passes[idx]);
+        if let Some(pass) = ready.into_iter().chain(running).min() {
+            self.global_pass = pass;
+        }
+
+        self.passes[job] = self.passes[job].max(self.global_pass);
+        self.ready.insert((self.passes[job], job));
+        self.share.join(job);
+    }
+
+    // SELECT: the smallest pass at the start of every quantum
+    fn pick(&mut self, ctx: &mut Context) -> Option<Pick> {
+        let detail = match ctx.logging() {
+            true => {
+                let pass = self
+                    .ready
+                    .iter()
+                    .map(|&(pass, x)| format!("{}={}", ctx.jobs[x].name, pass))
+                    .collect::<Vec<String>>();
+                format!("PASS: {}", pass.join(" "))
+            }
+            false => String::new(),
+        };
+
+        let (_, job) = self.ready.pop_first()?;
+        self.running = Some(job);
+
+        Some(Pick::new(job).slice(self.quantum).detail(detail))
+    }
+
+    fn queue(&self, _cpu: usize) -> Vec<usize> {
+        self.ready.iter().map(|&(_, job)| job).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.ready.len()
+    }
+
+    fn ran(&mut self, job: usize, ticks: u32, _ctx: &mut Context) {
+        self.passes[job] += self.strides[job] * ticks as u64;
+        self.share.ran(job, ticks);
+    }
+
+    fn expired(&mut self, job: usize, _ctx: &mut Context) {
+        self.running = None;
+        self.ready.insert((self.passes[job], job));
+    }
+
+    fn done(&mut self, job: usize, _ctx: &mut Context) {
+        self.running = None;
+        self.share.leave(job);
+    }
+}
+
+impl Scheduler for StrideScheduler {
+    fn new(mut jobs: Vec<Job>) -> StrideScheduler {
+        assert!(jobs.iter().all(|job| job.tickets > 0), "every job needs a ticket");
+        assert!(
+            jobs.iter().all(|job| job.tickets as u64 <= STRIDE1),
+            "a job may have at most {} tickets",
+            STRIDE1
+        );
+        jobs.sort_by_key(|job| job.arrival_time);
+
+        StrideScheduler {
+            share: ShareReport::new(&jobs),
+            jobs,
+        }
+    }
+
+    /// Selects the smallest pass every `config.quantum` ticks.
+    fn run(&mut self, config: &SchedulerConfig) -> Schedule {
+        let mut schedule = Schedule::new("Stride（步长调度）");
+
+        let mut queue = StrideQueue {
+            quantum: config.quantum,
+            strides: self
+                .jobs
+                .iter()
+                .map(|job| STRIDE1 / job.tickets as u64)
+                .collect(),
+            passes: vec![0; self.jobs.len()],
+            global_pass: 0,
+            ready: BTreeSet::new(),
+            running: None,
+            share: ShareReport::new(&self.jobs),
+        };
+        Engine::new(&mut self.jobs, config).run(&mut queue, &mut schedule);
+        self.share = queue.share;
+
+        schedule.note(format!("quantum: {}", config.quantum));
+        schedule.note(self.share.to_string());
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{stride::StrideScheduler, Job, Scheduler, SchedulerConfig};
+
+    #[test]
+    fn test() {
+        let jobs: Vec<Job> = vec![
+            Job { tickets: 300, ..Job::new("A", 0, 12) },
+            Job { tickets: 100, ..Job::new("B", 0, 12) },
+            Job { tickets: 200, ..Job::new("C", 4, 4) },
+        ];
+
+        let mut scheduler = StrideScheduler::new(jobs);
+        let schedule = scheduler.run(&SchedulerConfig::default());
+        schedule.print();
+
+        // A gets 3 of the first 4 ticks, then A, B and C share 3:1:2
+        assert_eq!(scheduler.share.received(0), 12);
+        let first_ticks = schedule
+            .segments
+            .iter()
+            .flat_map(|segment| vec![segment.job.as_deref().unwrap(); segment.ticks() as usize])
+            .take(4)
+            .collect::<Vec<&str>>();
+        assert_eq!(first_ticks, vec!["A", "B", "A", "A"]);
+
+        for idx in 0..3 {
+            let ratio = scheduler.share.received(idx) as f32 / scheduler.share.entitled(idx);
+            assert!((0.8..1.25).contains(&ratio), "job {} got {}", idx, ratio);
+        }
+
+        // large ticket counts still get distinct strides
+        let jobs: Vec<Job> = vec![
+            Job { tickets: 300_000, ..Job::new("A", 0, 16) },
+            Job { tickets: 100_000, ..Job::new("B", 0, 16) },
+        ];
+        let schedule = StrideScheduler::new(jobs).run(&SchedulerConfig::default());
+        let a_ticks = schedule
+            .segments
+            .iter()
+            .flat_map(|segment| vec![segment.job.as_deref().unwrap(); segment.ticks() as usize])
+            .take(8)
+            .filter(|&job| job == "A")
+            .count();
+        assert_eq!(a_ticks, 6);
+    }
+}
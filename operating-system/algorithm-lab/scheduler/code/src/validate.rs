@@ -0,0 +1,552 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{Job, Schedule, Segment};
+
+/// An invariant a schedule broke.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// A segment of `job` starts at `time`, before the job arrived.
+    BeforeArrival { job: String, time: u32 },
+    /// A segment of `job` starts at `time`, before `predecessor` finished.
+    BeforePredecessor {
+        job: String,
+        predecessor: String,
+        time: u32,
+    },
+    /// The segments of `job` add up to `ran` ticks instead of its service time.
+    ServiceTime { job: String, ran: u32, service: u32 },
+    /// `first` and `second` both hold `cpu` at `time`.
+    Overlap {
+        cpu: usize,
+        time: u32,
+        first: String,
+        second: String,
+    },
+    /// `idle` CPUs had nothing to do from `time` while `ready` jobs waited.
+    NotWorkConserving {
+        time: u32,
+        idle: usize,
+        ready: usize,
+    },
+    /// The finish time of `job` is not where its last segment ends or not the time of
+    /// its FINISHED event.
+    FinishTime {
+        job: String,
+        finish: u32,
+        expected: Option<u32>,
+    },
+    /// `job` is missing from the finished jobs, or finished twice.
+    Unfinished { job: String },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::BeforeArrival { job, time } => {
+                write!(f, "{} runs at {} before it arrives", job, time)
+            }
+            Violation::BeforePredecessor {
+                job,
+                predecessor,
+                time,
+            } => write!(
+                f,
+                "{} runs at {} before {} finished",
+                job, time, predecessor
+            ),
+            Violation::ServiceTime { job, ran, service } => {
+                write!(f, "{} ran {} ticks of its {}", job, ran, service)
+            }
+            Violation::Overlap {
+                cpu,
+                time,
+                first,
+                second,
+            } => write!(
+                f,
+                "{} and {} overlap on CPU{} at {}",
+                first, second, cpu, time
+            ),
+            Violation::NotWorkConserving { time, idle, ready } => write!(
+                f,
+                "{} CPUs idle at {} while {} jobs are ready",
+                idle, time, ready
+            ),
+            Violation::FinishTime {
+                job,
+                finish,
+                expected,
+            } => match expected {
+                Some(expected) => {
+                    write!(f, "{} finished at {} instead of {}", job, finish, expected)
+                }
+                None => write!(f, "{} finished at {} without running", job, finish),
+            },
+            Violation::Unfinished { job } => write!(f, "{} did not finish once", job),
+        }
+    }
+}
+
+/// Checks a schedule against the jobs it was run on. The I/O periods of a job come
+/// from its BLOCKED and READY events, without an event log the work-conserving check
+/// is skipped for jobs with I/O bursts.
+pub struct Validator<'a> {
+    jobs: &'a [Job],
+    cpus: usize,
+    work_conserving: bool,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(jobs: &'a [Job]) -> Validator<'a> {
+        Validator {
+            jobs,
+            cpus: 1,
+            work_conserving: false,
+        }
+    }
+
+    pub fn cpus(mut self, cpus: usize) -> Validator<'a> {
+        assert!(cpus > 0, "at least one CPU is needed");
+
+        self.cpus = cpus;
+        self
+    }
+
+    /// Whether a CPU may never idle while a job is ready, true of every policy that
+    /// does not hold CPUs back, e.g. for a time window or an affinity.
+    pub fn work_conserving(mut self, work_conserving: bool) -> Validator<'a> {
+        self.work_conserving = work_conserving;
+        self
+    }
+
+    /// Every violation found, in the order of the checks.
+    pub fn check(&self, schedule: &Schedule) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        self.check_jobs(schedule, &mut violations);
+        self.check_overlap(schedule, &mut violations);
+        if self.work_conserving {
+            self.check_work_conserving(schedule, &mut violations);
+        }
+
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+
+    // ARRIVAL, SERVICE AND FINISH: every job on its own
+    fn check_jobs(&self, schedule: &Schedule, violations: &mut Vec<Violation>) {
+        let mut segments: HashMap<&str, Vec<&Segment>> = HashMap::new();
+        for segment in &schedule.segments {
+            if let Some(job) = &segment.job {
+                segments.entry(job).or_default().push(segment);
+            }
+        }
+
+        let mut finished: HashMap<&str, Vec<u32>> = HashMap::new();
+        for process in &schedule.events {
+            if let Some(job) = process
+                .job
+                .as_ref()
+                .filter(|_| process.status == "FINISHED")
+            {
+                finished
+                    .entry(&job.name)
+                    .or_default()
+                    .push(process.current_time);
+            }
+        }
+
+        for job in self.jobs {
+            let name = job.name.clone();
+            let segments = segments.remove(job.name.as_str()).unwrap_or_default();
+
+            if let Some(first) = segments.iter().min_by_key(|segment| segment.start) {
+                if first.start < job.arrival_time {
+                    violations.push(Violation::BeforeArrival {
+                        job: name.clone(),
+                        time: first.start,
+                    });
+                }
+
+                for predecessor in &job.predecessors {
+                    let finish = schedule.finish_time(predecessor);
+                    if finish.is_none_or(|finish| first.start < finish) {
+                        violations.push(Violation::BeforePredecessor {
+                            job: name.clone(),
+                            predecessor: predecessor.clone(),
+                            time: first.start,
+                        });
+                    }
+                }
+            }
+
+            let running = segments.iter().filter(|segment| !segment.overhead);
+            let ran = running.clone().map(|segment| segment.ticks()).sum::<u32>();
+            if ran != job.service_time {
+                violations.push(Violation::ServiceTime {
+                    job: name.clone(),
+                    ran,
+                    service: job.service_time,
+                });
+            }
+
+            let metrics = schedule
+                .jobs
+                .iter()
+                .filter(|metrics| metrics.name == job.name)
+                .collect::<Vec<_>>();
+            let [metrics] = metrics[..] else {
+                violations.push(Violation::Unfinished { job: name });
+                continue;
+            };
+
+            let last_end = running.map(|segment| segment.end).max();
+            let events = finished.remove(job.name.as_str()).unwrap_or_default();
+            let event_mismatch = !schedule.events.is_empty() && events != [metrics.finish_time];
+            if last_end != Some(metrics.finish_time) || event_mismatch {
+                violations.push(Violation::FinishTime {
+                    job: name,
+                    finish: metrics.finish_time,
+                    expected: last_end,
+                });
+            }
+        }
+    }
+
+    // OVERLAP: the segments of a CPU follow each other
+    fn check_overlap(&self, schedule: &Schedule, violations: &mut Vec<Violation>) {
+        let mut lanes: HashMap<usize, Vec<&Segment>> = HashMap::new();
+        for segment in schedule
+            .segments
+            .iter()
+            .filter(|segment| segment.job.is_some())
+        {
+            lanes.entry(segment.cpu).or_default().push(segment);
+        }
+
+        let mut cpus = lanes.keys().copied().collect::<Vec<usize>>();
+        cpus.sort();
+
+        for cpu in cpus {
+            let lane = lanes.get_mut(&cpu).unwrap();
+            lane.sort_by_key(|segment| (segment.start, segment.end));
+
+            for pair in lane.windows(2) {
+                if pair[1].start < pair[0].end {
+                    violations.push(Violation::Overlap {
+                        cpu,
+                        time: pair[1].start,
+                        first: pair[0].job.clone().unwrap(),
+                        second: pair[1].job.clone().unwrap(),
+                    });
+                }
+            }
+        }
+    }
+
+    // WORK CONSERVING: sweeps the changes in the ready jobs and the busy CPUs
+    fn check_work_conserving(&self, schedule: &Schedule, violations: &mut Vec<Violation>) {
+        let finish_times = schedule
+            .jobs
+            .iter()
+            .map(|job| (job.name.as_str(), job.finish_time))
+            .collect::<HashMap<&str, u32>>();
+
+        // (time, present jobs, busy CPUs), a present job is ready unless it runs
+        let mut changes: Vec<(u32, i64, i64)> = Vec::new();
+
+        for job in self.jobs {
+            if job.has_io() && schedule.events.is_empty() {
+                return;
+            }
+
+            let Some(&finish) = finish_times.get(job.name.as_str()) else {
+                return;
+            };
+
+            // present once it arrived and its predecessors finished
+            let mut ready = job.arrival_time;
+            for predecessor in &job.predecessors {
+                let Some(&finish) = finish_times.get(predecessor.as_str()) else {
+                    return;
+                };
+                ready = ready.max(finish);
+            }
+
+            changes.push((ready, 1, 0));
+            changes.push((finish, -1, 0));
+        }
+
+        for segment in schedule
+            .segments
+            .iter()
+            .filter(|segment| segment.job.is_some())
+        {
+            changes.push((segment.start, 0, 1));
+            changes.push((segment.end, 0, -1));
+        }
+
+        // BLOCKED until READY: on the I/O device or waiting for it
+        let mut blocked: HashMap<&str, u32> = HashMap::new();
+        for process in &schedule.events {
+            let Some(job) = &process.job else {
+                continue;
+            };
+
+            match process.status.as_str() {
+                "BLOCKED" => {
+                    blocked.insert(&job.name, process.current_time);
+                }
+                "READY" => {
+                    if let Some(start) = blocked.remove(job.name.as_str()) {
+                        changes.push((start, -1, 0));
+                        changes.push((process.current_time, 1, 0));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        changes.sort();
+
+        let (mut present, mut busy) = (0, 0);
+        for (idx, &(time, jobs, cpus)) in changes.iter().enumerate() {
+            present += jobs;
+            busy += cpus;
+
+            // only once every change at this time is in
+            if changes.get(idx + 1).is_some_and(|next| next.0 == time) {
+                continue;
+            }
+
+            let idle = self.cpus as i64 - busy;
+            let ready = present - busy;
+            if idle > 0 && ready > 0 {
+                violations.push(Violation::NotWorkConserving {
+                    time,
+                    idle: idle as usize,
+                    ready: ready as usize,
+                });
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::{
+        cfs::CfsScheduler,
+        dag::ListScheduler,
+        fair::FairShareScheduler,
+        fcfs::FcfsScheduler,
+        hrrn::HrrnScheduler,
+        lottery::LotteryScheduler,
+        mlfq::MlfqScheduler,
+        mlq::{InterQueuePolicy, MlqScheduler},
+        priority::{PriorityMode, PriorityScheduler},
+        rr::{RrScheduler, RrVariant},
+        sjf::SjfScheduler,
+        smp::{Balancing, QueueMode, SmpScheduler},
+        srtf::SrtfScheduler,
+        stride::StrideScheduler,
+        validate::{Validator, Violation},
+        Aging, Burst, Job, JobClass, Schedule, Scheduler, SchedulerConfig, Segment,
+    };
+
+    #[test]
+    fn test() {
+        let jobs = vec![Job::new("A", 1, 2), Job::new("B", 1, 2)];
+        let segment = |job: &str, start: u32, end: u32| Segment {
+            job: Some(job.to_string()),
+            cpu: 0,
+            start,
+            end,
+            overhead: false,
+        };
+
+        let schedule = FcfsScheduler::new(jobs.clone()).run(&SchedulerConfig::default());
+        let validator = Validator::new(&jobs).work_conserving(true);
+        assert_eq!(validator.check(&schedule), Ok(()));
+
+        // A starts early and overlaps B, which ends a tick short of its finish
+        let mut broken = Schedule::new("broken");
+        broken.segments = vec![segment("A", 0, 2), segment("B", 1, 3)];
+        broken.finish(&Job {
+            finish_time: Some(2),
+            whole_time: Some(2),
+            weight_whole_time: Some(1.0),
+            ..jobs[0].clone()
+        });
+        broken.finish(&Job {
+            finish_time: Some(4),
+            whole_time: Some(3),
+            weight_whole_time: Some(1.5),
+            ..jobs[1].clone()
+        });
+
+        assert_eq!(
+            validator.check(&broken),
+            Err(vec![
+                Violation::BeforeArrival {
+                    job: "A".to_string(),
+                    time: 0,
+                },
+                Violation::FinishTime {
+                    job: "B".to_string(),
+                    finish: 4,
+                    expected: Some(3),
+                },
+                Violation::Overlap {
+                    cpu: 0,
+                    time: 1,
+                    first: "A".to_string(),
+                    second: "B".to_string(),
+                },
+                Violation::NotWorkConserving {
+                    time: 3,
+                    idle: 1,
+                    ready: 1,
+                },
+            ])
+        );
+    }
+
+    /// Up to a dozen jobs with gaps between the arrivals and a few owners, some with
+    /// an I/O burst and some waiting for earlier jobs.
+    fn workload() -> impl Strategy<Value = Vec<Job>> {
+        let job = (
+            0..6u32,
+            1..8u32,
+            0..5u32,
+            1..4u32,
+            -5..5i32,
+            prop::option::of((1..4u32, 1..4u32)),
+            prop::collection::vec(any::<prop::sample::Index>(), 0..3),
+        );
+
+        prop::collection::vec(job, 1..12).prop_map(|specs| {
+            let mut arrival_time = 0;
+            let classes = [JobClass::System, JobClass::Interactive, JobClass::Batch];
+
+            specs
+                .into_iter()
+                .enumerate()
+                .map(|(idx, (gap, service, priority, tickets, nice, io, deps))| {
+                    arrival_time += gap;
+                    let name = format!("J{}", idx);
+                    let job = match io {
+                        Some((io, rest)) => Job::with_bursts(
+                            &name,
+                            arrival_time,
+                            Burst::alternating(&[service, io, rest]),
+                        ),
+                        None => Job::new(&name, arrival_time, service),
+                    };
+
+                    let predecessors = match idx {
+                        0 => Vec::new(),
+                        _ => deps
+                            .iter()
+                            .map(|before| format!("J{}", before.index(idx)))
+                            .collect(),
+                    };
+
+                    Job {
+                        priority,
+                        tickets: tickets * 100,
+                        nice,
+                        class: classes[idx % 3],
+                        owner: (idx % 3 > 0).then(|| format!("U{}", idx % 3)),
+                        predecessors,
+                        ..job
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// Every policy with the CPUs it runs on and whether it is work conserving.
+    fn schedulers(jobs: &[Job]) -> Vec<(Box<dyn Scheduler>, usize, bool)> {
+        let jobs = || jobs.to_vec();
+        let rr = |variant| Box::new(RrScheduler::new(jobs()).variant(variant));
+        let smp = |mode| Box::new(SmpScheduler::new(jobs()).mode(mode));
+
+        vec![
+            (Box::new(FcfsScheduler::new(jobs())), 1, true),
+            (Box::new(SjfScheduler::new(jobs())), 1, true),
+            (rr(RrVariant::Fixed), 1, true),
+            (rr(RrVariant::Median), 1, true),
+            (rr(RrVariant::Mean), 1, true),
+            (rr(RrVariant::Priority), 1, true),
+            (rr(RrVariant::Virtual), 1, true),
+            (Box::new(SrtfScheduler::new(jobs())), 1, true),
+            (Box::new(PriorityScheduler::new(jobs())), 1, true),
+            (
+                Box::new(PriorityScheduler::new(jobs()).mode(PriorityMode::Preemptive)),
+                1,
+                true,
+            ),
+            (Box::new(HrrnScheduler::new(jobs())), 1, true),
+            (Box::new(MlfqScheduler::new(jobs())), 1, true),
+            (Box::new(MlfqScheduler::new(jobs()).boost(7)), 1, true),
+            (Box::new(MlqScheduler::new(jobs())), 1, true),
+            (
+                Box::new(
+                    MlqScheduler::new(jobs()).inter_queue(InterQueuePolicy::TimeSliced {
+                        cycle: 10,
+                        shares: [50, 30, 20],
+                    }),
+                ),
+                1,
+                false,
+            ),
+            (Box::new(LotteryScheduler::new(jobs())), 1, true),
+            (Box::new(StrideScheduler::new(jobs())), 1, true),
+            (Box::new(CfsScheduler::new(jobs())), 1, true),
+            (smp(QueueMode::Global), 2, true),
+            (smp(QueueMode::PerCpu(Balancing::WorkStealing)), 2, true),
+            (smp(QueueMode::PerCpu(Balancing::None)), 2, false),
+            (smp(QueueMode::PerCpu(Balancing::Periodic(3))), 2, false),
+            (Box::new(ListScheduler::new(jobs()).cpus(2)), 2, true),
+            (
+                Box::new(FairShareScheduler::new(jobs()).weight("U1", 3)),
+                1,
+                true,
+            ),
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn policies(
+            jobs in workload(),
+            quantum in 1..4u32,
+            context_switch in 0..3u32,
+            aging in prop::option::of(1..4u32),
+        ) {
+            let config = SchedulerConfig::default()
+                .quantum(quantum)
+                .context_switch(context_switch);
+            let config = match aging {
+                Some(interval) => config.aging(Aging::new(interval, 3)),
+                None => config,
+            };
+
+            for (mut scheduler, cpus, work_conserving) in schedulers(&jobs) {
+                let schedule = scheduler.run(&config);
+                let validator = Validator::new(&jobs)
+                    .cpus(cpus)
+                    .work_conserving(work_conserving);
+
+                prop_assert_eq!(validator.check(&schedule), Ok(()), "{}", schedule.algorithm);
+            }
+        }
+    }
+}
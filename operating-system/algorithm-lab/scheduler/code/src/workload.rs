@@ -0,0 +1,220 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::Job;
+
+/// How the gaps between two arrivals are drawn, both keep the mean the load asks for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arrivals {
+    /// Exponential gaps, a Poisson process.
+    Poisson,
+    /// Gaps uniform between 0 and twice the mean.
+    Uniform,
+}
+
+/// Distribution of the service times, every time is at least one tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ServiceTimes {
+    Exponential {
+        mean: f64,
+    },
+    /// Uniform from `min` to `max`, both included.
+    Uniform {
+        min: u32,
+        max: u32,
+    },
+    /// `long` with the probability `long_share`, `short` otherwise.
+    Bimodal {
+        short: u32,
+        long: u32,
+        long_share: f64,
+    },
+}
+
+impl ServiceTimes {
+    pub fn mean(&self) -> f64 {
+        match *self {
+            ServiceTimes::Exponential { mean } => mean,
+            ServiceTimes::Uniform { min, max } => (min + max) as f64 / 2.0,
+            ServiceTimes::Bimodal {
+                short,
+                long,
+                long_share,
+            } => short as f64 * (1.0 - long_share) + long as f64 * long_share,
+        }
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> u32 {
+        match *self {
+            ServiceTimes::Exponential { mean } => (exponential(rng, mean).round() as u32).max(1),
+            ServiceTimes::Uniform { min, max } => rng.gen_range(min..=max),
+            ServiceTimes::Bimodal {
+                short,
+                long,
+                long_share,
+            } => match rng.gen_bool(long_share) {
+                true => long,
+                false => short,
+            },
+        }
+    }
+}
+
+/// Inverse transform sample of an exponential distribution.
+fn exponential(rng: &mut StdRng, mean: f64) -> f64 {
+    -mean * (1.0 - rng.gen::<f64>()).ln()
+}
+
+/// Builds a random workload, the same seed always builds the same jobs.
+///
+/// The load factor is the share of one CPU the jobs ask for, the mean service time
+/// over the mean gap between arrivals. Above 1 the queue keeps growing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workload {
+    jobs: usize,
+    load: f64,
+    seed: u64,
+    arrivals: Arrivals,
+    service_times: ServiceTimes,
+}
+
+impl Workload {
+    pub fn new(jobs: usize) -> Workload {
+        Workload {
+            jobs,
+            load: 0.8,
+            seed: 0,
+            arrivals: Arrivals::Poisson,
+            service_times: ServiceTimes::Exponential { mean: 5.0 },
+        }
+    }
+
+    pub fn load(mut self, load: f64) -> Workload {
+        assert!(load > 0.0, "load factor must be positive");
+
+        self.load = load;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Workload {
+        self.seed = seed;
+        self
+    }
+
+    pub fn arrivals(mut self, arrivals: Arrivals) -> Workload {
+        self.arrivals = arrivals;
+        self
+    }
+
+    pub fn service_times(mut self, service_times: ServiceTimes) -> Workload {
+        match service_times {
+            ServiceTimes::Exponential { mean } => assert!(mean > 0.0, "mean must be positive"),
+            ServiceTimes::Uniform { min, max } => {
+                assert!(0 < min && min <= max, "needs 0 < min <= max")
+            }
+            ServiceTimes::Bimodal {
+                short,
+                long,
+                long_share,
+            } => {
+                assert!(short > 0 && long > 0, "service times must be positive");
+                assert!((0.0..=1.0).contains(&long_share), "share must be in 0..=1");
+            }
+        }
+
+        self.service_times = service_times;
+        self
+    }
+
+    /// Jobs `J1`, `J2`, ... sorted by arrival, the first arrives at 0.
+    pub fn generate(&self) -> Vec<Job> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mean_gap = self.service_times.mean() / self.load;
+
+        // the arrival is kept as a float so the rounding does not add up
+        let mut arrival: f64 = 0.0;
+
+        (1..=self.jobs)
+            .map(|idx| {
+                if idx > 1 {
+                    arrival += match self.arrivals {
+                        Arrivals::Poisson => exponential(&mut rng, mean_gap),
+                        Arrivals::Uniform => rng.gen_range(0.0..2.0 * mean_gap),
+                    };
+                }
+
+                let service_time = self.service_times.sample(&mut rng);
+                Job::new(&format!("J{}", idx), arrival as u32, service_time)
+            })
+            .collect()
+    }
+}
+
+/// `name,arrival_time,service_time` lines, the format of the job files under `data/`.
+pub fn to_csv(jobs: &[Job]) -> String {
+    jobs.iter()
+        .map(|job| format!("{},{},{}\n", job.name, job.arrival_time, job.service_time))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::workload::{to_csv, Arrivals, ServiceTimes, Workload};
+
+    #[test]
+    fn test() {
+        let workload = Workload::new(2000).seed(7).load(0.5);
+        let jobs = workload.generate();
+
+        assert_eq!(jobs.len(), 2000);
+        assert_eq!(jobs[0].arrival_time, 0);
+        assert!(jobs
+            .windows(2)
+            .all(|pair| pair[0].arrival_time <= pair[1].arrival_time));
+        assert!(jobs.iter().all(|job| job.service_time > 0));
+
+        // the same seed gives the same jobs, another seed other ones
+        let again = workload.clone().generate();
+        let other = workload.seed(8).generate();
+        let times = |jobs: &[crate::Job]| {
+            jobs.iter()
+                .map(|job| (job.arrival_time, job.service_time))
+                .collect::<Vec<(u32, u32)>>()
+        };
+        assert_eq!(times(&jobs), times(&again));
+        assert_ne!(times(&jobs), times(&other));
+
+        // the jobs ask for about half of the CPU
+        let demand = jobs.iter().map(|job| job.service_time).sum::<u32>() as f64;
+        let load = demand / jobs.last().unwrap().arrival_time as f64;
+        assert!((0.45..0.55).contains(&load), "load {}", load);
+
+        assert_eq!(
+            to_csv(&jobs[..2]),
+            format!(
+                "J1,0,{}\nJ2,{},{}\n",
+                jobs[0].service_time, jobs[1].arrival_time, jobs[1].service_time
+            )
+        );
+    }
+
+    #[test]
+    fn distributions() {
+        let jobs = Workload::new(500)
+            .arrivals(Arrivals::Uniform)
+            .service_times(ServiceTimes::Bimodal {
+                short: 1,
+                long: 20,
+                long_share: 0.1,
+            })
+            .generate();
+
+        let long = jobs.iter().filter(|job| job.service_time == 20).count();
+        assert!(jobs.iter().all(|job| [1, 20].contains(&job.service_time)));
+        assert!((25..75).contains(&long), "{} long jobs", long);
+
+        let jobs = Workload::new(500)
+            .service_times(ServiceTimes::Uniform { min: 3, max: 6 })
+            .generate();
+        assert!(jobs.iter().all(|job| (3..=6).contains(&job.service_time)));
+    }
+}